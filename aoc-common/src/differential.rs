@@ -0,0 +1,240 @@
+//! Differential testing: checking an optimized solver against a simple reference implementation
+//! on many random inputs, and shrinking any input they disagree on to a minimal one.
+//!
+//! A day declares a `Differential` next to the optimized function, and a test runs it:
+//!
+//! ```
+//! use aoc_common::differential::Differential;
+//!
+//! fn fast_sum(nums: &[u64]) -> u64 {
+//!     nums.iter().sum()
+//! }
+//!
+//! fn reference_sum(nums: &[u64]) -> u64 {
+//!     nums.iter().fold(0, |acc, n| acc + n)
+//! }
+//!
+//! const SUM: Differential<Vec<u64>, u64> = Differential {
+//!     name: "fast_sum",
+//!     fast: |nums| fast_sum(nums),
+//!     reference: |nums| reference_sum(nums),
+//! };
+//!
+//! SUM.check(200, |rng| (0..rng.below(10)).map(|_| rng.below(100)).collect());
+//! ```
+
+use crate::property::{find_failure, minimize};
+use crate::Rng;
+use std::fmt::Debug;
+
+/// An optimized function and a reference implementation which should always agree.
+pub struct Differential<I, O> {
+    pub name: &'static str,
+    pub fast: fn(&I) -> O,
+    pub reference: fn(&I) -> O,
+}
+
+impl<I: Debug, O: PartialEq + Debug> Differential<I, O> {
+    /// Compares the two implementations on `cases` inputs from `generate`, shrinking any input
+    /// they disagree on with `Shrink`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the shrunk input and both outputs if the implementations disagree.
+    pub fn check(&self, cases: u64, generate: impl Fn(&mut Rng) -> I)
+    where
+        I: Shrink,
+    {
+        self.check_with(cases, generate, I::shrink)
+    }
+
+    /// Like `check`, but shrinks with `shrink`, for inputs with constraints that `Shrink` doesn't
+    /// know about. `shrink` should only return valid inputs.
+    pub fn check_with(
+        &self,
+        cases: u64,
+        generate: impl Fn(&mut Rng) -> I,
+        shrink: impl Fn(&I) -> Vec<I>,
+    ) {
+        let disagrees = |input: &I| (self.fast)(input) != (self.reference)(input);
+        let Some((seed, input)) = find_failure(cases, generate, disagrees) else {
+            return;
+        };
+        let input = minimize(input, shrink, disagrees);
+        panic!(
+            "{} disagrees with its reference implementation (seed {}):\n  input: {:?}\n  {}: \
+             {:?}\n  reference: {:?}",
+            self.name,
+            seed,
+            input,
+            self.name,
+            (self.fast)(&input),
+            (self.reference)(&input)
+        );
+    }
+}
+
+/// Values which can propose simpler versions of themselves, for shrinking failing inputs.
+pub trait Shrink: Sized {
+    /// Returns values strictly simpler than `self`, most aggressive first. Shrinking must
+    /// terminate, so a value must never be among its own (transitive) shrinks.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! shrink_unsigned {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let mut shrinks = vec![0, self / 2, self.saturating_sub(1)];
+                shrinks.retain(|shrink| shrink < self);
+                shrinks.dedup();
+                shrinks
+            }
+        }
+    )*};
+}
+
+macro_rules! shrink_signed {
+    ($($t:ty),*) => {$(
+        impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                // Towards zero, and negative numbers to their (simpler) absolute value.
+                let mut shrinks = vec![0, self / 2, self - self.signum()];
+                if *self < 0 {
+                    shrinks.insert(1, self.saturating_neg());
+                }
+                shrinks.retain(|shrink| {
+                    shrink.unsigned_abs() < self.unsigned_abs()
+                        || (*self < 0 && *shrink > 0)
+                });
+                shrinks.dedup();
+                shrinks
+            }
+        }
+    )*};
+}
+
+shrink_unsigned!(u8, u32, u64, usize);
+shrink_signed!(i32, i64, isize);
+
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+    /// Removes halves, then single elements, then shrinks single elements.
+    fn shrink(&self) -> Vec<Self> {
+        let mut shrinks = Vec::new();
+        if self.len() > 1 {
+            let half = self.len() / 2;
+            shrinks.push(self[half..].to_vec());
+            shrinks.push(self[..half].to_vec());
+        }
+        for i in 0..self.len() {
+            let mut removed = self.clone();
+            removed.remove(i);
+            shrinks.push(removed);
+        }
+        for (i, item) in self.iter().enumerate() {
+            for shrunk in item.shrink() {
+                let mut replaced = self.clone();
+                replaced[i] = shrunk;
+                shrinks.push(replaced);
+            }
+        }
+        shrinks
+    }
+}
+
+impl Shrink for String {
+    /// Removes halves, then single characters.
+    fn shrink(&self) -> Vec<Self> {
+        let chars: Vec<char> = self.chars().collect();
+        let mut shrinks = Vec::new();
+        if chars.len() > 1 {
+            let half = chars.len() / 2;
+            shrinks.push(chars[half..].iter().collect());
+            shrinks.push(chars[..half].iter().collect());
+        }
+        for i in 0..chars.len() {
+            shrinks.push(chars[..i].iter().chain(&chars[i + 1..]).collect());
+        }
+        shrinks
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let firsts = self.0.shrink().into_iter().map(|a| (a, self.1.clone()));
+        let seconds = self.1.shrink().into_iter().map(|b| (self.0.clone(), b));
+        firsts.chain(seconds).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Differential, Shrink};
+    use crate::property::minimize;
+
+    fn max_or_zero(nums: &[i64]) -> i64 {
+        nums.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Wrong whenever a number over 50 comes after a larger one.
+    fn buggy_max(nums: &[i64]) -> i64 {
+        nums.iter()
+            .fold(0, |acc, &n| if n > acc || n > 50 { n } else { acc })
+    }
+
+    #[test]
+    fn test_agreement() {
+        let max: Differential<Vec<i64>, i64> = Differential {
+            name: "max",
+            fast: |nums| max_or_zero(nums),
+            reference: |nums| nums.iter().fold(0, |acc, &n| acc.max(n)),
+        };
+        max.check(100, |rng| {
+            (0..rng.below(20)).map(|_| rng.range(0..=99)).collect()
+        });
+    }
+
+    #[test]
+    fn test_disagreement_is_shrunk() {
+        let max: Differential<Vec<i64>, i64> = Differential {
+            name: "buggy_max",
+            fast: |nums| buggy_max(nums),
+            reference: |nums| max_or_zero(nums),
+        };
+        let disagrees = |nums: &Vec<i64>| buggy_max(nums) != max_or_zero(nums);
+        let input = minimize(vec![3, 99, 7, 80, 12, 60], Shrink::shrink, disagrees);
+        assert_eq!(input, vec![52, 51]);
+        let err = std::panic::catch_unwind(|| {
+            max.check(100, |rng| (0..20).map(|_| rng.range(0..=99)).collect())
+        })
+        .unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(message.contains("input: [52, 51]"), "{}", message);
+    }
+
+    #[test]
+    fn test_shrink() {
+        assert_eq!(10u64.shrink(), vec![0, 5, 9]);
+        assert_eq!(1u64.shrink(), vec![0]);
+        assert!(0u64.shrink().is_empty());
+        assert_eq!((-10i64).shrink(), vec![0, 10, -5, -9]);
+        assert_eq!(10i64.shrink(), vec![0, 5, 9]);
+        assert_eq!(
+            vec![1u8, 2].shrink(),
+            vec![
+                vec![2],
+                vec![1],
+                vec![2],
+                vec![1],
+                vec![0, 2],
+                vec![1, 0],
+                vec![1, 1]
+            ]
+        );
+        assert_eq!((1u8, 0u8).shrink(), vec![(0, 0)]);
+        assert_eq!(
+            "héy".to_string().shrink(),
+            vec!["éy", "h", "éy", "hy", "hé"]
+        );
+    }
+}
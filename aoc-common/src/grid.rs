@@ -0,0 +1,439 @@
+use crate::parse::numbered_lines;
+use crate::{Direction, InputError, Line, ParseError, Point};
+
+/// A type which a single character of a grid in the puzzle input can be parsed into.
+pub trait FromCell: Sized {
+    /// What a valid character looks like, for error messages, such as "`.` or `#`".
+    const EXPECTED: &'static str;
+
+    /// Parses the character, or returns `None` if it isn't a valid cell.
+    fn from_cell(c: char) -> Option<Self>;
+}
+
+/// A type which can be written back as a single character of a grid, the inverse of `FromCell`.
+pub trait ToCell {
+    fn to_cell(&self) -> char;
+}
+
+impl FromCell for u8 {
+    const EXPECTED: &'static str = "an ASCII character";
+
+    fn from_cell(c: char) -> Option<Self> {
+        c.is_ascii().then_some(c as u8)
+    }
+}
+
+impl ToCell for u8 {
+    fn to_cell(&self) -> char {
+        *self as char
+    }
+}
+
+impl FromCell for char {
+    const EXPECTED: &'static str = "a character";
+
+    fn from_cell(c: char) -> Option<Self> {
+        Some(c)
+    }
+}
+
+impl ToCell for char {
+    fn to_cell(&self) -> char {
+        *self
+    }
+}
+
+/// Read access to cells by position, shared by the dense `Grid` and the sparse `SparseGrid`.
+pub trait Cells {
+    type Cell;
+
+    /// Returns the cell at the given point, or `None` if the point lies outside the map.
+    fn get(&self, point: Point) -> Option<&Self::Cell>;
+
+    /// Returns every stored point along with its cell. A `Grid` yields all of them row by row; a
+    /// `SparseGrid` yields only those which were set, in no particular order.
+    fn cells(&self) -> impl Iterator<Item = (Point, &Self::Cell)>;
+
+    fn contains(&self, point: Point) -> bool {
+        self.get(point).is_some()
+    }
+
+    /// Returns whether the map has finitely many points. Only a `SparseGrid` with a default cell
+    /// and no size is infinite.
+    fn is_finite(&self) -> bool {
+        true
+    }
+
+    /// Returns the neighbouring point in the given direction, or `None` if it lies outside the
+    /// map.
+    fn step(&self, point: Point, direction: Direction) -> Option<Point> {
+        Some(point.step(direction)).filter(|&next| self.contains(next))
+    }
+}
+
+/// A rectangle of cells, stored row by row in one flat `Vec`. Plain bytes by default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T = u8> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Makes a `width` by `height` grid with every cell set to `cell`.
+    pub fn new(width: usize, height: usize, cell: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![cell; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Makes a grid from its rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows are not all the same width.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "rows must all be the same width"
+        );
+        Grid {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns each row as a slice, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// Returns the index into `cells` of the given point, or `None` if it lies outside the grid.
+    fn index_of(&self, point: Point) -> Option<usize> {
+        let (row, col) = (
+            usize::try_from(point.row).ok()?,
+            usize::try_from(point.col).ok()?,
+        );
+        (row < self.height && col < self.width).then(|| row * self.width + col)
+    }
+
+    /// Returns the cell at the given point mutably, or `None` if the point lies outside the grid.
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+}
+
+impl<T> Cells for Grid<T> {
+    type Cell = T;
+
+    fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, cell)| {
+            let point = Point::new((i / width) as isize, (i % width) as isize);
+            (point, cell)
+        })
+    }
+}
+
+impl<T> std::ops::Index<Point> for Grid<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if the point lies outside the grid.
+    fn index(&self, point: Point) -> &T {
+        match self.get(point) {
+            Some(cell) => cell,
+            None => panic!(
+                "{:?} is outside the {}x{} grid",
+                point, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> std::ops::IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(point) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside the {}x{} grid", point, width, height),
+        }
+    }
+}
+
+impl<T: FromCell> Grid<T> {
+    /// Parses a grid with one line per row, skipping blank lines. Every row must be as wide as
+    /// the first.
+    pub fn parse(r: impl std::io::BufRead) -> Result<Self, InputError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for line in numbered_lines(r) {
+            let (number, text) = line?;
+            if text.is_empty() {
+                continue;
+            }
+            let start = cells.len();
+            for c in text.chars() {
+                match T::from_cell(c) {
+                    Some(cell) => cells.push(cell),
+                    None => {
+                        // Go back for the position of the first invalid cell only once there is
+                        // one.
+                        let invalid = Line::new(number, &text)
+                            .chars()
+                            .find(|token| token.text.chars().all(|c| T::from_cell(c).is_none()))
+                            .unwrap();
+                        return Err(invalid.error(T::EXPECTED).into());
+                    }
+                }
+            }
+            let row_width = cells.len() - start;
+            let width = *width.get_or_insert(row_width);
+            if row_width != width {
+                let line = Line::new(number, &text);
+                let token = line.chars().nth(width).unwrap_or(line.end());
+                return Err(token
+                    .error(&format!("a row {} cells wide, like the first", width))
+                    .into());
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+}
+
+impl<T: FromCell> std::str::FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::parse(s.as_bytes()) {
+            Ok(grid) => Ok(grid),
+            Err(InputError::Parse(err)) => Err(err),
+            Err(InputError::Io(err)) => unreachable!("cannot fail to read a str: {}", err),
+        }
+    }
+}
+
+impl<T: ToCell> std::fmt::Display for Grid<T> {
+    /// Writes the grid back out as text, one line per row.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            let text: String = row.iter().map(ToCell::to_cell).collect();
+            writeln!(f, "{}", text)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cells, FromCell, Grid, ToCell};
+    use crate::{Direction, Point};
+
+    const WORD_SEARCH: &str = "
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    const MAP: &str = "
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Tile {
+        Floor,
+        Wall,
+    }
+
+    impl FromCell for Tile {
+        const EXPECTED: &'static str = "`.` or `#`";
+
+        fn from_cell(c: char) -> Option<Self> {
+            match c {
+                '.' => Some(Tile::Floor),
+                '#' => Some(Tile::Wall),
+                _ => None,
+            }
+        }
+    }
+
+    impl ToCell for Tile {
+        fn to_cell(&self) -> char {
+            match self {
+                Tile::Floor => '.',
+                Tile::Wall => '#',
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid = WORD_SEARCH.parse().unwrap();
+        let expected: Vec<Vec<u8>> = vec![
+            "MMMSXXMASM".as_bytes().to_vec(),
+            "MSAMXMSMSA".as_bytes().to_vec(),
+            "AMXSXMAAMM".as_bytes().to_vec(),
+            "MSAMASMSMX".as_bytes().to_vec(),
+            "XMASAMXAMM".as_bytes().to_vec(),
+            "XXAMMXXAMA".as_bytes().to_vec(),
+            "SMSMSASXSS".as_bytes().to_vec(),
+            "SAXAMASAAA".as_bytes().to_vec(),
+            "MAMMMXMMMM".as_bytes().to_vec(),
+            "MXMXAXMASX".as_bytes().to_vec(),
+        ];
+        assert_eq!(grid.rows().collect::<Vec<_>>(), expected);
+        let grid: Grid = MAP.parse().unwrap();
+        let expected: Vec<Vec<u8>> = vec![
+            "....#.....".as_bytes().to_vec(),
+            ".........#".as_bytes().to_vec(),
+            "..........".as_bytes().to_vec(),
+            "..#.......".as_bytes().to_vec(),
+            ".......#..".as_bytes().to_vec(),
+            "..........".as_bytes().to_vec(),
+            ".#..^.....".as_bytes().to_vec(),
+            "........#.".as_bytes().to_vec(),
+            "#.........".as_bytes().to_vec(),
+            "......#...".as_bytes().to_vec(),
+        ];
+        assert_eq!(grid.rows().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_parse_cells() {
+        let grid: Grid<Tile> = "#.\n.#\n".parse().unwrap();
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&Tile::Wall));
+        assert_eq!(grid.get(Point::new(0, 1)), Some(&Tile::Floor));
+        let err = "#.\n.x\n".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 2, "x"));
+        assert_eq!(err.expected, "`.` or `#`");
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        let grid: Grid<Tile> = "#..#\n.##.\n".parse().unwrap();
+        assert_eq!(grid.to_string(), "#..#\n.##.\n");
+        let grid: Grid = WORD_SEARCH.parse().unwrap();
+        assert_eq!(
+            grid.to_string(),
+            WORD_SEARCH.trim_start().to_string() + "\n"
+        );
+        assert_eq!(grid.to_string().parse::<Grid>().unwrap(), grid);
+    }
+
+    #[test]
+    fn test_get() {
+        let grid: Grid = MAP.parse().unwrap();
+        assert_eq!(grid.get(Point::new(0, 4)), Some(&b'#'));
+        assert_eq!(grid.get(Point::new(6, 4)), Some(&b'^'));
+        assert_eq!(grid.get(Point::new(9, 9)), Some(&b'.'));
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.get(Point::new(10, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 10)), None);
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let grid: Grid = WORD_SEARCH.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (10, 10));
+        let grid: Grid<Tile> = "#..#\n.##.\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 2));
+        let grid: Grid = "".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.rows().count(), 0);
+    }
+
+    #[test]
+    fn test_parse_rejects_ragged_rows() {
+        let err = "#..#\n.##\n".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 4, ""));
+        assert_eq!(err.expected, "a row 4 cells wide, like the first");
+        let err = "#..#\n\n.##..\n".parse::<Grid<Tile>>().unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (3, 5, "."));
+    }
+
+    #[test]
+    fn test_index() {
+        let mut grid = Grid::new(3, 2, Tile::Floor);
+        grid[Point::new(1, 2)] = Tile::Wall;
+        assert_eq!(grid[Point::new(1, 2)], Tile::Wall);
+        assert_eq!(grid.to_string(), "...\n..#\n");
+        *grid.get_mut(Point::new(0, 0)).unwrap() = Tile::Wall;
+        assert_eq!(grid.get_mut(Point::new(0, 3)), None);
+        assert_eq!(
+            Grid::from_rows(vec![
+                vec![Tile::Wall, Tile::Floor, Tile::Floor],
+                vec![Tile::Floor, Tile::Floor, Tile::Wall]
+            ]),
+            grid
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the 3x2 grid")]
+    fn test_index_out_of_bounds() {
+        let grid = Grid::new(3, 2, Tile::Floor);
+        let _ = &grid[Point::new(2, 0)];
+    }
+
+    #[test]
+    fn test_step() {
+        let grid: Grid = MAP.parse().unwrap();
+        let corner = Point::new(0, 9);
+        assert_eq!(grid.step(corner, Direction::Down), Some(Point::new(1, 9)));
+        assert_eq!(
+            grid.step(corner, Direction::DownLeft),
+            Some(Point::new(1, 8))
+        );
+        assert_eq!(grid.step(corner, Direction::Up), None);
+        assert_eq!(grid.step(corner, Direction::Right), None);
+        let obstacles: Vec<Point> = grid
+            .cells()
+            .filter(|&(_, &cell)| cell == b'#')
+            .map(|(point, _)| point)
+            .take(2)
+            .collect();
+        assert_eq!(obstacles, vec![Point::new(0, 4), Point::new(1, 9)]);
+    }
+}
@@ -0,0 +1,94 @@
+//! Reading puzzle inputs without holding a second copy of them in memory.
+//!
+//! Files are memory-mapped, and everything else is read through `BufRead`, so that a day can
+//! parse an input of any size by streaming it line by line with `parse::numbered_lines` or chunk
+//! by chunk with `for_each_chunk`.
+
+use memmap2::Mmap;
+use std::path::Path;
+
+/// The contents of a puzzle input, either mapped from a file or held in memory.
+pub enum Bytes {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl std::ops::Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Bytes::Mapped(map) => map,
+            Bytes::Owned(bytes) => bytes,
+        }
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Bytes::Owned(bytes)
+    }
+}
+
+/// Memory-maps the file at `path`. Empty files, which cannot be mapped on every platform, are
+/// returned as an empty buffer instead.
+pub fn map_file(path: &Path) -> std::io::Result<Bytes> {
+    let file = std::fs::File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Ok(Bytes::Owned(Vec::new()));
+    }
+    // SAFETY: the map is only read, and puzzle inputs are not modified while a day runs. If one
+    // is truncated underneath us anyway, the worst outcome is a crash rather than a wrong answer.
+    let map = unsafe { Mmap::map(&file)? };
+    Ok(Bytes::Mapped(map))
+}
+
+/// Calls `f` with each chunk of `r` as it is read, without collecting them. Chunks split the
+/// input at arbitrary points, even within a line or a UTF-8 character.
+pub fn for_each_chunk(
+    mut r: impl std::io::BufRead,
+    mut f: impl FnMut(&[u8]),
+) -> std::io::Result<()> {
+    loop {
+        let chunk = match r.fill_buf() {
+            Ok([]) => return Ok(()),
+            Ok(chunk) => chunk,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        f(chunk);
+        let len = chunk.len();
+        r.consume(len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Write};
+
+    #[test]
+    fn test_for_each_chunk() {
+        let text = "the quick brown fox\njumps over the lazy dog\n";
+        let mut chunks = Vec::new();
+        let r = BufReader::with_capacity(4, text.as_bytes());
+        super::for_each_chunk(r, |chunk| chunks.push(chunk.to_vec())).unwrap();
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 4));
+        assert_eq!(chunks.concat(), text.as_bytes());
+    }
+
+    #[test]
+    fn test_map_file() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.txt");
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(b"1 2\n3 4\n")
+            .unwrap();
+        assert_eq!(&*super::map_file(&path).unwrap(), b"1 2\n3 4\n");
+        std::fs::File::create(&path).unwrap();
+        assert_eq!(&*super::map_file(&path).unwrap(), b"");
+        assert!(super::map_file(&dir.join("missing.txt")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,22 @@
+//! Pieces shared between the daily puzzle crates.
+
+pub mod differential;
+pub mod grid;
+pub mod input;
+pub mod output;
+pub mod parse;
+pub mod point;
+pub mod property;
+pub mod render;
+pub mod rng;
+pub mod solution;
+pub mod sparse;
+pub mod timing;
+
+pub use grid::{Cells, FromCell, Grid, ToCell};
+pub use parse::{InputError, Line, ParseError};
+pub use point::{Direction, Point};
+pub use rng::Rng;
+pub use solution::{parse_stdin_or_exit, Part, Report, Solution};
+pub use sparse::SparseGrid;
+pub use timing::Elapsed;
@@ -0,0 +1,106 @@
+use crate::Part;
+use std::time::Duration;
+
+/// How answers are printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// `day N part P: ANSWER`, one line per part.
+    #[default]
+    Text,
+    /// One JSON object per line, with the fields of `Record`.
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format `{}`, expected `text` or `json`", s)),
+        }
+    }
+}
+
+/// The answer to one part of one day, and how long it took to solve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+impl Record {
+    /// Formats the record as a single line, without the trailing newline.
+    pub fn format(&self, format: Format) -> String {
+        match format {
+            Format::Text => format!("day {} part {}: {}", self.day, self.part, self.answer),
+            Format::Json => format!(
+                r#"{{"day":{},"part":{},"answer":{},"elapsed_ns":{}}}"#,
+                self.day,
+                self.part,
+                json_string(&self.answer),
+                self.elapsed.as_nanos()
+            ),
+        }
+    }
+}
+
+/// Quotes `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, Record};
+    use crate::Part;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format() {
+        let record = Record {
+            day: 7,
+            part: Part::Two,
+            answer: "11387".into(),
+            elapsed: Duration::from_micros(1500),
+        };
+        assert_eq!(record.format(Format::Text), "day 7 part 2: 11387");
+        assert_eq!(
+            record.format(Format::Json),
+            r#"{"day":7,"part":2,"answer":"11387","elapsed_ns":1500000}"#
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(super::json_string("plain"), r#""plain""#);
+        assert_eq!(
+            super::json_string("a \"b\"\\\n\u{1}"),
+            r#""a \"b\"\\\n\u0001""#
+        );
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert_eq!("text".parse(), Ok(Format::Text));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
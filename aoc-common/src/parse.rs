@@ -0,0 +1,284 @@
+use std::str::FromStr;
+
+/// A malformed token in the puzzle input, with enough context to point at it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column, in characters, where the offending token starts.
+    pub column: usize,
+    /// The offending token, or an empty string if the line ended too early.
+    pub token: String,
+    /// A description of what should have been there, such as "a number".
+    pub expected: String,
+    /// The full text of the offending line.
+    pub text: String,
+}
+
+impl std::fmt::Display for ParseError {
+    /// Formats the error like a compiler diagnostic, underlining the token in its line.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.token.is_empty() {
+            writeln!(f, "expected {}, found end of line", self.expected)?;
+        } else {
+            writeln!(f, "expected {}, found `{}`", self.expected, self.token)?;
+        }
+        let line_number = self.line.to_string();
+        let pad = " ".repeat(line_number.len());
+        writeln!(f, "{} --> line {}, column {}", pad, self.line, self.column)?;
+        writeln!(f, "{} |", pad)?;
+        writeln!(f, "{} | {}", line_number, self.text)?;
+        write!(
+            f,
+            "{} | {}{}",
+            pad,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.token.chars().count().max(1))
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An error reading the puzzle input, or a malformed token within it.
+#[derive(Debug)]
+pub enum InputError {
+    Io(std::io::Error),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Io(err) => write!(f, "cannot read input: {}", err),
+            InputError::Parse(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io(err) => Some(err),
+            InputError::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for InputError {
+    fn from(err: std::io::Error) -> Self {
+        InputError::Io(err)
+    }
+}
+
+impl From<ParseError> for InputError {
+    fn from(err: ParseError) -> Self {
+        InputError::Parse(err)
+    }
+}
+
+/// Returns the lines of `r` along with their 1-based line numbers.
+pub fn numbered_lines(
+    r: impl std::io::BufRead,
+) -> impl Iterator<Item = std::io::Result<(usize, String)>> {
+    r.lines()
+        .enumerate()
+        .map(|(i, line)| line.map(|line| (i + 1, line)))
+}
+
+/// One line of the puzzle input, which knows where it is for error reporting.
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(number: usize, text: &'a str) -> Self {
+        Line { number, text }
+    }
+
+    /// Returns the whitespace-separated tokens of the line.
+    pub fn fields(self) -> impl Iterator<Item = Token<'a>> {
+        self.text
+            .split_whitespace()
+            .map(move |field| self.token(field))
+    }
+
+    /// Returns the pieces of the line between occurrences of `separator`.
+    pub fn split(self, separator: char) -> impl Iterator<Item = Token<'a>> {
+        self.text
+            .split(separator)
+            .map(move |piece| self.token(piece))
+    }
+
+    /// Returns each character of the line as a token.
+    pub fn chars(self) -> impl Iterator<Item = Token<'a>> {
+        self.text
+            .char_indices()
+            .map(move |(i, c)| self.token(&self.text[i..i + c.len_utf8()]))
+    }
+
+    /// Splits the line around the first occurrence of `separator`.
+    pub fn split_once(self, separator: char) -> Result<(Token<'a>, Token<'a>), ParseError> {
+        let Some((left, right)) = self.text.split_once(separator) else {
+            return Err(self.end().error(&format!("`{}`", separator)));
+        };
+        Ok((self.token(left), self.token(right)))
+    }
+
+    /// Returns an empty token just past the end of the line, for reporting missing tokens.
+    pub fn end(self) -> Token<'a> {
+        self.token(&self.text[self.text.len()..])
+    }
+
+    /// Takes the next token from `tokens`, or reports that `expected` is missing.
+    pub fn next_token(
+        self,
+        tokens: &mut impl Iterator<Item = Token<'a>>,
+        expected: &str,
+    ) -> Result<Token<'a>, ParseError> {
+        tokens.next().ok_or_else(|| self.end().error(expected))
+    }
+
+    /// Reports the first of `tokens`, if any remain.
+    pub fn expect_end(
+        self,
+        tokens: &mut impl Iterator<Item = Token<'a>>,
+    ) -> Result<(), ParseError> {
+        match tokens.next() {
+            Some(token) => Err(token.error("end of line")),
+            None => Ok(()),
+        }
+    }
+
+    /// Makes a token of `piece`, which must be a subslice of the line's text.
+    fn token(self, piece: &'a str) -> Token<'a> {
+        let offset = piece.as_ptr() as usize - self.text.as_ptr() as usize;
+        Token {
+            line: self,
+            offset,
+            text: piece,
+        }
+    }
+}
+
+/// A piece of a `Line`.
+#[derive(Clone, Copy, Debug)]
+pub struct Token<'a> {
+    line: Line<'a>,
+    /// The byte offset of the token within the line.
+    offset: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Token<'a> {
+    /// Removes leading and trailing whitespace from the token.
+    pub fn trim(self) -> Self {
+        self.line.token(self.text.trim())
+    }
+
+    /// Returns the whitespace-separated tokens within this token.
+    pub fn fields(self) -> impl Iterator<Item = Token<'a>> {
+        self.text
+            .split_whitespace()
+            .map(move |field| self.line.token(field))
+    }
+
+    /// Parses the token, reporting that `expected` should have been there if it fails.
+    pub fn parse<T: FromStr>(self, expected: &str) -> Result<T, ParseError> {
+        self.text.parse().map_err(|_| self.error(expected))
+    }
+
+    pub fn error(self, expected: &str) -> ParseError {
+        ParseError {
+            line: self.line.number,
+            column: self.line.text[..self.offset].chars().count() + 1,
+            token: self.text.to_string(),
+            expected: expected.to_string(),
+            text: self.line.text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Line, ParseError};
+
+    #[test]
+    fn test_token_parse() {
+        let line = Line::new(4, "3   x3");
+        let mut fields = line.fields();
+        let first = fields.next().unwrap();
+        assert_eq!(first.parse::<isize>("a number"), Ok(3));
+        let second = fields.next().unwrap();
+        assert_eq!(
+            second.parse::<isize>("a number"),
+            Err(ParseError {
+                line: 4,
+                column: 5,
+                token: "x3".into(),
+                expected: "a number".into(),
+                text: "3   x3".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_split_once() {
+        let line = Line::new(1, "190: 10 19");
+        let (total, rest) = line.split_once(':').unwrap();
+        assert_eq!(total.text, "190");
+        let columns: Vec<usize> = rest.fields().map(|field| field.error("").column).collect();
+        assert_eq!(columns, vec![6, 9]);
+        let err = Line::new(2, "190 10 19").split_once(':').unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (10, ""));
+        assert_eq!(err.expected, "`:`");
+    }
+
+    #[test]
+    fn test_trim_keeps_column() {
+        let line = Line::new(1, "  12 |34");
+        let (left, right) = line.split_once('|').unwrap();
+        assert_eq!(left.trim().error("").column, 3);
+        assert_eq!(right.trim().error("").column, 7);
+    }
+
+    #[test]
+    fn test_expect_end() {
+        let line = Line::new(3, "1 2 3");
+        let mut fields = line.fields();
+        line.next_token(&mut fields, "a number").unwrap();
+        line.next_token(&mut fields, "a number").unwrap();
+        let err = line.expect_end(&mut fields).unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (5, "3"));
+        let err = line.next_token(&mut fields, "a number").unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (6, ""));
+    }
+
+    #[test]
+    fn test_display() {
+        let err = Line::new(12, "3   x3")
+            .fields()
+            .nth(1)
+            .unwrap()
+            .error("a number");
+        assert_eq!(
+            err.to_string(),
+            "expected a number, found `x3`
+   --> line 12, column 5
+   |
+12 | 3   x3
+   |     ^^"
+        );
+        let err = Line::new(7, "47").end().error("`|`");
+        assert_eq!(
+            err.to_string(),
+            "expected `|`, found end of line
+  --> line 7, column 3
+  |
+7 | 47
+  |   ^"
+        );
+    }
+}
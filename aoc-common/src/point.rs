@@ -0,0 +1,187 @@
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A position in a grid, or the offset between two positions. Rows grow downwards and columns
+/// grow to the right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Point {
+    pub const fn new(row: isize, col: isize) -> Self {
+        Point { row, col }
+    }
+
+    /// Returns the neighbouring point in the given direction.
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.delta()
+    }
+
+    /// Returns the neighbours in the four orthogonal directions, clockwise from up.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Direction::ORTHOGONAL.into_iter().map(move |d| self.step(d))
+    }
+
+    /// Returns the neighbours in all eight directions, clockwise from up.
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |d| self.step(d))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point {
+        self.step(direction)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, n: isize) -> Point {
+        Point::new(self.row * n, self.col * n)
+    }
+}
+
+/// One of the eight compass directions on a grid, named as they appear on screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// The four orthogonal directions, clockwise from up.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// All eight directions, clockwise from up.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// Returns the offset of one step in this direction.
+    pub fn delta(self) -> Point {
+        match self {
+            Direction::Up => Point::new(-1, 0),
+            Direction::UpRight => Point::new(-1, 1),
+            Direction::Right => Point::new(0, 1),
+            Direction::DownRight => Point::new(1, 1),
+            Direction::Down => Point::new(1, 0),
+            Direction::DownLeft => Point::new(1, -1),
+            Direction::Left => Point::new(0, -1),
+            Direction::UpLeft => Point::new(-1, -1),
+        }
+    }
+
+    /// Rotates clockwise by `eighths` eighths of a turn, or anticlockwise if negative.
+    pub fn rotate(self, eighths: isize) -> Direction {
+        let index = Direction::ALL.iter().position(|&d| d == self).unwrap() as isize;
+        Direction::ALL[(index + eighths).rem_euclid(8) as usize]
+    }
+
+    /// Rotates a quarter turn clockwise.
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    /// Rotates a quarter turn anticlockwise.
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    /// Returns the opposite direction.
+    pub fn reverse(self) -> Direction {
+        self.rotate(4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Point};
+
+    #[test]
+    fn test_arithmetic() {
+        let p = Point::new(2, 5);
+        assert_eq!(p + Point::new(1, -1), Point::new(3, 4));
+        assert_eq!(p - Point::new(1, -1), Point::new(1, 6));
+        assert_eq!(-p, Point::new(-2, -5));
+        assert_eq!(Direction::DownLeft.delta() * 3, Point::new(3, -3));
+        assert_eq!(p + Direction::Up, Point::new(1, 5));
+        assert_eq!(p.step(Direction::Right), Point::new(2, 6));
+    }
+
+    #[test]
+    fn test_rotation() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::UpLeft.rotate(1), Direction::Up);
+        assert_eq!(Direction::Up.rotate(-9), Direction::UpLeft);
+        for d in Direction::ALL {
+            assert_eq!(d.reverse().reverse(), d);
+            assert_eq!(d.reverse().delta(), -d.delta());
+            assert_eq!(d.turn_right().turn_left(), d);
+        }
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let p = Point::new(0, 0);
+        let four: Vec<Point> = p.neighbours4().collect();
+        assert_eq!(
+            four,
+            vec![
+                Point::new(-1, 0),
+                Point::new(0, 1),
+                Point::new(1, 0),
+                Point::new(0, -1)
+            ]
+        );
+        assert_eq!(p.neighbours8().count(), 8);
+        assert!(p.neighbours8().all(|n| n != p));
+    }
+}
@@ -0,0 +1,133 @@
+//! Property testing: checking that something holds for many random inputs, and shrinking any
+//! input it fails for to a minimal one. Differential tests are built on the same machinery.
+//!
+//! A test declares a `Property` and runs it:
+//!
+//! ```
+//! use aoc_common::property::Property;
+//!
+//! const SORT_IS_IDEMPOTENT: Property<Vec<u64>> = Property {
+//!     name: "sorting twice is the same as sorting once",
+//!     holds: |nums| {
+//!         let mut once = nums.clone();
+//!         once.sort();
+//!         let mut twice = once.clone();
+//!         twice.sort();
+//!         once == twice
+//!     },
+//! };
+//!
+//! SORT_IS_IDEMPOTENT.check(200, |rng| (0..rng.below(10)).map(|_| rng.below(100)).collect());
+//! ```
+
+use crate::differential::Shrink;
+use crate::Rng;
+use std::fmt::Debug;
+
+/// The seed of the first case, unless `AOC_DIFFERENTIAL_SEED` is set. Each case after it uses the
+/// next seed.
+const DEFAULT_SEED: u64 = 0;
+
+/// Something which should be true of every input.
+pub struct Property<I> {
+    pub name: &'static str,
+    pub holds: fn(&I) -> bool,
+}
+
+impl<I: Debug> Property<I> {
+    /// Checks the property on `cases` inputs from `generate`, shrinking any input it fails for
+    /// with `Shrink`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the shrunk input if the property fails.
+    pub fn check(&self, cases: u64, generate: impl Fn(&mut Rng) -> I)
+    where
+        I: Shrink,
+    {
+        self.check_with(cases, generate, I::shrink)
+    }
+
+    /// Like `check`, but shrinks with `shrink`, for inputs with constraints that `Shrink` doesn't
+    /// know about. `shrink` should only return valid inputs.
+    pub fn check_with(
+        &self,
+        cases: u64,
+        generate: impl Fn(&mut Rng) -> I,
+        shrink: impl Fn(&I) -> Vec<I>,
+    ) {
+        let fails = |input: &I| !(self.holds)(input);
+        let Some((seed, input)) = find_failure(cases, generate, fails) else {
+            return;
+        };
+        let input = minimize(input, shrink, fails);
+        panic!(
+            "property `{}` does not hold (seed {}):\n  input: {:?}",
+            self.name, seed, input
+        );
+    }
+}
+
+/// Returns the seed and input of the first of `cases` generated inputs which `fails`, if any.
+pub(crate) fn find_failure<I>(
+    cases: u64,
+    generate: impl Fn(&mut Rng) -> I,
+    fails: impl Fn(&I) -> bool,
+) -> Option<(u64, I)> {
+    let first_seed = std::env::var("AOC_DIFFERENTIAL_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(DEFAULT_SEED);
+    (first_seed..first_seed + cases)
+        .map(|seed| (seed, generate(&mut Rng::new(seed))))
+        .find(|(_, input)| fails(input))
+}
+
+/// Repeatedly replaces `input` with the first smaller input that still `fails`, until there are
+/// none.
+pub(crate) fn minimize<I>(
+    mut input: I,
+    shrink: impl Fn(&I) -> Vec<I>,
+    fails: impl Fn(&I) -> bool,
+) -> I {
+    while let Some(smaller) = shrink(&input)
+        .into_iter()
+        .find(|candidate| fails(candidate))
+    {
+        input = smaller;
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Property;
+
+    #[test]
+    fn test_holds() {
+        let reverse_twice: Property<Vec<i64>> = Property {
+            name: "reversing twice changes nothing",
+            holds: |nums| nums.iter().rev().rev().eq(nums.iter()),
+        };
+        reverse_twice.check(100, |rng| {
+            (0..rng.below(20)).map(|_| rng.range(-99..=99)).collect()
+        });
+    }
+
+    #[test]
+    fn test_failure_is_shrunk() {
+        let small: Property<Vec<u64>> = Property {
+            name: "every number is at most 40",
+            holds: |nums| nums.iter().all(|&n| n <= 40),
+        };
+        let err = std::panic::catch_unwind(|| {
+            small.check(100, |rng| (0..10).map(|_| rng.below(50)).collect())
+        })
+        .unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            message,
+            "property `every number is at most 40` does not hold (seed 0):\n  input: [41]"
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Pictures of a grid, with highlighted cells, paths and arrows drawn over it, as ANSI-coloured
+//! text, SVG or PNG.
+//!
+//! ```
+//! use aoc_common::render::Picture;
+//! use aoc_common::{Direction, Grid, Point};
+//!
+//! let grid: Grid = "#..\n...\n".parse().unwrap();
+//! let picture = Picture::new(&grid)
+//!     .highlight([Point::new(0, 0)])
+//!     .arrow(Point::new(1, 1), Direction::Right);
+//! assert_eq!(picture.ansi(), "\x1b[30;43m#\x1b[0m..\n.\x1b[1;31m→\x1b[0m.\n");
+//! ```
+
+use crate::{Cells, Direction, Point, ToCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+/// How many pixels wide and high each cell is in SVG output.
+const SVG_CELL: usize = 16;
+
+/// How many pixels wide and high each cell is in PNG output.
+const PNG_CELL: usize = 4;
+
+/// The output formats a `Picture` can be drawn in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Ansi,
+    Svg,
+    Png,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Format::Ansi),
+            "svg" => Ok(Format::Svg),
+            "png" => Ok(Format::Png),
+            _ => Err(format!(
+                "unknown format `{}`, expected `ansi`, `svg` or `png`",
+                s
+            )),
+        }
+    }
+}
+
+/// A grid with overlays to draw over it. Arrows are drawn over highlights, which are drawn over
+/// paths.
+///
+/// The picture covers the smallest rectangle holding every cell the grid yields from
+/// [`Cells::cells`] and every overlay, so a sparse grid is cropped to what has been set on it.
+pub struct Picture<'a, G> {
+    grid: &'a G,
+    highlights: BTreeSet<Point>,
+    paths: Vec<Vec<Point>>,
+    arrows: BTreeMap<Point, Direction>,
+}
+
+/// What to draw in one cell, after working out which overlays cover it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layer {
+    Cell,
+    Path,
+    Highlight,
+    Arrow(Direction),
+}
+
+impl<'a, G> Picture<'a, G>
+where
+    G: Cells,
+    G::Cell: ToCell,
+{
+    pub fn new(grid: &'a G) -> Self {
+        Picture {
+            grid,
+            highlights: BTreeSet::new(),
+            paths: Vec::new(),
+            arrows: BTreeMap::new(),
+        }
+    }
+
+    /// Highlights each of the cells.
+    pub fn highlight(mut self, points: impl IntoIterator<Item = Point>) -> Self {
+        self.highlights.extend(points);
+        self
+    }
+
+    /// Draws a path through the cells, in order.
+    pub fn path(mut self, points: impl IntoIterator<Item = Point>) -> Self {
+        self.paths.push(points.into_iter().collect());
+        self
+    }
+
+    /// Draws an arrow in the cell, pointing in the direction.
+    pub fn arrow(mut self, point: Point, direction: Direction) -> Self {
+        self.arrows.insert(point, direction);
+        self
+    }
+
+    /// Draws the picture in the given format.
+    pub fn draw(&self, format: Format) -> Vec<u8> {
+        match format {
+            Format::Ansi => self.ansi().into_bytes(),
+            Format::Svg => self.svg().into_bytes(),
+            Format::Png => self.png(),
+        }
+    }
+
+    /// Returns the top-left and bottom-right corners of the picture, or `None` if it is empty.
+    fn bounds(&self) -> Option<(Point, Point)> {
+        let points = self
+            .grid
+            .cells()
+            .map(|(point, _)| point)
+            .chain(self.highlights.iter().copied())
+            .chain(self.paths.iter().flatten().copied())
+            .chain(self.arrows.keys().copied());
+        points.fold(None, |bounds, point| match bounds {
+            None => Some((point, point)),
+            Some((min, max)) => Some((
+                Point::new(min.row.min(point.row), min.col.min(point.col)),
+                Point::new(max.row.max(point.row), max.col.max(point.col)),
+            )),
+        })
+    }
+
+    /// Returns each row of the picture from the top, as each cell's character and top layer. Points
+    /// outside the grid are drawn as spaces.
+    fn rows(&self) -> Vec<Vec<(char, Layer)>> {
+        let Some((min, max)) = self.bounds() else {
+            return Vec::new();
+        };
+        let on_path: BTreeSet<Point> = self.paths.iter().flatten().copied().collect();
+        (min.row..=max.row)
+            .map(|row| {
+                (min.col..=max.col)
+                    .map(|col| {
+                        let point = Point::new(row, col);
+                        let c = self.grid.get(point).map_or(' ', ToCell::to_cell);
+                        let layer = if let Some(&direction) = self.arrows.get(&point) {
+                            Layer::Arrow(direction)
+                        } else if self.highlights.contains(&point) {
+                            Layer::Highlight
+                        } else if on_path.contains(&point) {
+                            Layer::Path
+                        } else {
+                            Layer::Cell
+                        };
+                        (c, layer)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Draws the picture as text with ANSI colour codes, one line per row. Highlights are
+    /// yellow, paths blue, and arrows replace their cell with a red arrow.
+    pub fn ansi(&self) -> String {
+        let mut out = String::new();
+        for row in self.rows() {
+            for (c, layer) in row {
+                match layer {
+                    Layer::Cell => out.push(c),
+                    Layer::Path => write!(out, "\x1b[37;44m{}\x1b[0m", c).unwrap(),
+                    Layer::Highlight => write!(out, "\x1b[30;43m{}\x1b[0m", c).unwrap(),
+                    Layer::Arrow(direction) => {
+                        write!(out, "\x1b[1;31m{}\x1b[0m", arrow_char(direction)).unwrap()
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws the picture as an SVG document, with each cell's character as text, paths as lines
+    /// through the centres of their cells, and arrows as triangles. Paths are drawn first, so
+    /// highlighted cells cover them.
+    pub fn svg(&self) -> String {
+        let rows = self.rows();
+        let min = self.bounds().map_or(Point::default(), |(min, _)| min);
+        let (width, height) = (rows.first().map_or(0, Vec::len), rows.len());
+        let mut out = String::new();
+        writeln!(
+            out,
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" "#,
+                r#"font-family="monospace" font-size="{}" text-anchor="middle">"#
+            ),
+            width * SVG_CELL,
+            height * SVG_CELL,
+            SVG_CELL * 3 / 4
+        )
+        .unwrap();
+        writeln!(
+            out,
+            r##"<rect width="100%" height="100%" fill="#0f0f23"/>"##
+        )
+        .unwrap();
+        let centre = |point: Point| {
+            let offset = point - min;
+            (
+                offset.col as f64 * SVG_CELL as f64 + SVG_CELL as f64 / 2.0,
+                offset.row as f64 * SVG_CELL as f64 + SVG_CELL as f64 / 2.0,
+            )
+        };
+        for path in &self.paths {
+            let points: Vec<String> = path
+                .iter()
+                .map(|&point| {
+                    let (x, y) = centre(point);
+                    format!("{},{}", x, y)
+                })
+                .collect();
+            writeln!(
+                out,
+                concat!(
+                    r##"<polyline points="{}" fill="none" stroke="#4d79ff" "##,
+                    r##"stroke-width="2" stroke-linejoin="round"/>"##
+                ),
+                points.join(" ")
+            )
+            .unwrap();
+        }
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, &(c, layer)) in cells.iter().enumerate() {
+                let (x, y) = (col * SVG_CELL, row * SVG_CELL);
+                if layer == Layer::Highlight {
+                    writeln!(
+                        out,
+                        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#ffff66"/>"##,
+                        x, y, SVG_CELL, SVG_CELL
+                    )
+                    .unwrap();
+                }
+                if c != ' ' {
+                    let fill = match layer {
+                        Layer::Highlight => "#0f0f23",
+                        _ => "#cccccc",
+                    };
+                    writeln!(
+                        out,
+                        r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+                        x + SVG_CELL / 2,
+                        y + SVG_CELL * 3 / 4,
+                        fill,
+                        xml_escape(c)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        for (&point, &direction) in &self.arrows {
+            let (x, y) = centre(point);
+            let delta = direction.delta();
+            let length = ((delta.row * delta.row + delta.col * delta.col) as f64).sqrt();
+            let (dx, dy) = (delta.col as f64 / length, delta.row as f64 / length);
+            let size = SVG_CELL as f64;
+            let corners = [
+                (x + dx * 0.4 * size, y + dy * 0.4 * size),
+                (
+                    x - dx * 0.3 * size - dy * 0.3 * size,
+                    y - dy * 0.3 * size + dx * 0.3 * size,
+                ),
+                (
+                    x - dx * 0.3 * size + dy * 0.3 * size,
+                    y - dy * 0.3 * size - dx * 0.3 * size,
+                ),
+            ];
+            let corners: Vec<String> = corners
+                .iter()
+                .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                .collect();
+            writeln!(
+                out,
+                r##"<polygon points="{}" fill="#ff4d4d"/>"##,
+                corners.join(" ")
+            )
+            .unwrap();
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    /// Draws the picture as a PNG image, with each cell a small square. Cells holding `.` or
+    /// nothing are dark, other cells grey, and overlays are coloured as in `ansi`.
+    pub fn png(&self) -> Vec<u8> {
+        let rows = self.rows();
+        let (width, height) = (rows.first().map_or(0, Vec::len), rows.len());
+        let mut pixels = vec![0; width * PNG_CELL * height * PNG_CELL * 3];
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, &(c, layer)) in cells.iter().enumerate() {
+                let colour = match layer {
+                    Layer::Arrow(_) => [0xff, 0x4d, 0x4d],
+                    Layer::Highlight => [0xff, 0xff, 0x66],
+                    Layer::Path => [0x4d, 0x79, 0xff],
+                    Layer::Cell if c == '.' || c == ' ' => [0x0f, 0x0f, 0x23],
+                    Layer::Cell => [0xcc, 0xcc, 0xcc],
+                };
+                for y in row * PNG_CELL..(row + 1) * PNG_CELL {
+                    for x in col * PNG_CELL..(col + 1) * PNG_CELL {
+                        let i = (y * width * PNG_CELL + x) * 3;
+                        pixels[i..i + 3].copy_from_slice(&colour);
+                    }
+                }
+            }
+        }
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(
+            &mut out,
+            (width * PNG_CELL) as u32,
+            (height * PNG_CELL) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .expect("cannot fail to write to a Vec");
+        writer
+            .write_image_data(&pixels)
+            .expect("the image data is the right size");
+        writer.finish().expect("cannot fail to write to a Vec");
+        out
+    }
+}
+
+fn arrow_char(direction: Direction) -> char {
+    match direction {
+        Direction::Up => '↑',
+        Direction::UpRight => '↗',
+        Direction::Right => '→',
+        Direction::DownRight => '↘',
+        Direction::Down => '↓',
+        Direction::DownLeft => '↙',
+        Direction::Left => '←',
+        Direction::UpLeft => '↖',
+    }
+}
+
+fn xml_escape(c: char) -> String {
+    match c {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        c => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, Picture};
+    use crate::{Direction, Grid, Point, SparseGrid};
+
+    #[test]
+    fn test_ansi() {
+        let grid: Grid = "#..\n.<.\n".parse().unwrap();
+        let picture = Picture::new(&grid)
+            .path([Point::new(1, 1), Point::new(1, 2)])
+            .highlight([Point::new(0, 0)])
+            .arrow(Point::new(1, 1), Direction::Left);
+        assert_eq!(
+            picture.ansi(),
+            "\x1b[30;43m#\x1b[0m..\n.\x1b[1;31m←\x1b[0m\x1b[37;44m.\x1b[0m\n"
+        );
+        assert_eq!(Picture::new(&grid).ansi(), grid.to_string());
+    }
+
+    #[test]
+    fn test_sparse_bounds() {
+        let mut grid = SparseGrid::new(Some(b'.'));
+        grid.insert(Point::new(-1, 5), b'#');
+        let picture = Picture::new(&grid).highlight([Point::new(0, 7)]);
+        assert_eq!(picture.ansi(), "#..\n..\x1b[30;43m.\x1b[0m\n");
+        let grid: SparseGrid<u8> = SparseGrid::new(None);
+        assert_eq!(Picture::new(&grid).ansi(), "");
+    }
+
+    #[test]
+    fn test_svg() {
+        let grid: Grid = "<.\n..\n".parse().unwrap();
+        let svg = Picture::new(&grid)
+            .path([Point::new(0, 0), Point::new(0, 1), Point::new(1, 1)])
+            .arrow(Point::new(1, 1), Direction::Down)
+            .svg();
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="32" height="32""#)
+        );
+        assert!(svg.contains(">&lt;</text>"));
+        assert!(svg.contains(r#"<polyline points="8,8 24,8 24,24""#));
+        assert!(svg.contains(r#"<polygon points="24.0,30.4 19.2,19.2 28.8,19.2""#));
+        assert!(svg.ends_with("</svg>\n"));
+        let svg = Picture::new(&grid)
+            .path([Point::new(0, 0), Point::new(0, 1)])
+            .highlight([Point::new(0, 1)])
+            .svg();
+        let (path, highlight) = (svg.find("<polyline"), svg.find(r##"fill="#ffff66""##));
+        assert!(path.unwrap() < highlight.unwrap());
+    }
+
+    #[test]
+    fn test_png() {
+        let grid: Grid = "#..\n...\n".parse().unwrap();
+        let png = Picture::new(&grid)
+            .highlight([Point::new(1, 2)])
+            .draw(Format::Png);
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (12, 8));
+        let pixel = |x: usize, y: usize| &pixels[(y * 12 + x) * 3..(y * 12 + x) * 3 + 3];
+        assert_eq!(pixel(0, 0), [0xcc, 0xcc, 0xcc]);
+        assert_eq!(pixel(4, 0), [0x0f, 0x0f, 0x23]);
+        assert_eq!(pixel(11, 7), [0xff, 0xff, 0x66]);
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("svg".parse(), Ok(Format::Svg));
+        assert_eq!(
+            "gif".parse::<Format>(),
+            Err("unknown format `gif`, expected `ansi`, `svg` or `png`".to_string())
+        );
+    }
+}
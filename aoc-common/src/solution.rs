@@ -0,0 +1,118 @@
+use crate::InputError;
+
+/// One of the two parts of a day's puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    /// Returns the part with the given number, if it is 1 or 2.
+    pub fn from_number(n: u8) -> Option<Part> {
+        match n {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+/// A day's puzzle, split into parsing the input and solving each part against the parsed input.
+pub trait Solution {
+    /// The parsed puzzle input, shared by both parts.
+    type Input;
+    /// The answer produced by each part.
+    type Answer: Report;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Self::Input, InputError>;
+
+    fn part1(input: &Self::Input) -> Self::Answer;
+
+    fn part2(input: &Self::Input) -> Self::Answer;
+
+    fn solve(input: &Self::Input, part: Part) -> Self::Answer {
+        match part {
+            Part::One => Self::part1(input),
+            Part::Two => Self::part2(input),
+        }
+    }
+}
+
+/// An answer as the runner reports it: the text to print, or why the part has no answer.
+pub trait Report {
+    fn report(&self) -> Result<String, String>;
+}
+
+impl Report for usize {
+    fn report(&self) -> Result<String, String> {
+        Ok(self.to_string())
+    }
+}
+
+impl Report for isize {
+    fn report(&self) -> Result<String, String> {
+        Ok(self.to_string())
+    }
+}
+
+/// For parts which can fail, such as when their answer is too large for its type.
+impl<A: std::fmt::Display, E: std::fmt::Display> Report for Result<A, E> {
+    fn report(&self) -> Result<String, String> {
+        match self {
+            Ok(answer) => Ok(answer.to_string()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+/// Parses the input for `S` from stdin. If the input cannot be read or is malformed, the error is
+/// printed as a diagnostic and the process exits.
+pub fn parse_stdin_or_exit<S: Solution>() -> S::Input {
+    S::parse(std::io::stdin().lock()).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Part, Report};
+
+    #[test]
+    fn test_part_numbers() {
+        assert_eq!(Part::from_number(1), Some(Part::One));
+        assert_eq!(Part::from_number(2), Some(Part::Two));
+        assert_eq!(Part::from_number(0), None);
+        assert_eq!(Part::from_number(3), None);
+        for part in Part::BOTH {
+            assert_eq!(Part::from_number(part.number()), Some(part));
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        assert_eq!(31usize.report(), Ok("31".to_string()));
+        assert_eq!((-4isize).report(), Ok("-4".to_string()));
+        assert_eq!(Ok::<u8, String>(7).report(), Ok("7".to_string()));
+        assert_eq!(
+            Err::<u8, _>("too large").report(),
+            Err("too large".to_string())
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{Cells, Grid, Point};
+use std::collections::HashMap;
+
+/// A map which only stores the cells that have been set, for maps too large to hold densely or
+/// with no fixed bounds.
+///
+/// Without a default cell, only the points which have been set lie on the map. With one, every
+/// other point holds the default, so the map is unbounded unless it is also given a size with
+/// `bounded`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    default: Option<T>,
+    /// The width and height, if the map only covers the rectangle from the origin.
+    size: Option<(usize, usize)>,
+}
+
+impl<T> SparseGrid<T> {
+    /// Makes an empty, unbounded map, where unset points hold `default`, if there is one.
+    pub fn new(default: Option<T>) -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            size: None,
+        }
+    }
+
+    /// Limits the map to the `width` by `height` rectangle from the origin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a cell has already been set outside the rectangle.
+    pub fn bounded(mut self, width: usize, height: usize) -> Self {
+        self.size = Some((width, height));
+        if let Some(point) = self.cells.keys().find(|&&point| !self.in_bounds(point)) {
+            panic!("{:?} is outside the {}x{} grid", point, width, height);
+        }
+        self
+    }
+
+    /// Returns the width and height, if the map is bounded.
+    pub fn size(&self) -> Option<(usize, usize)> {
+        self.size
+    }
+
+    pub fn default(&self) -> Option<&T> {
+        self.default.as_ref()
+    }
+
+    /// Returns how many cells have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    fn in_bounds(&self, point: Point) -> bool {
+        match self.size {
+            Some((width, height)) => {
+                (0..height as isize).contains(&point.row)
+                    && (0..width as isize).contains(&point.col)
+            }
+            None => true,
+        }
+    }
+
+    /// Returns the cell at the given point, or `None` if the point lies outside the map.
+    pub fn get(&self, point: Point) -> Option<&T> {
+        if !self.in_bounds(point) {
+            return None;
+        }
+        self.cells.get(&point).or(self.default.as_ref())
+    }
+
+    /// Sets the cell at the given point, returning the cell which was set there before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map is bounded and the point lies outside it.
+    pub fn insert(&mut self, point: Point, cell: T) -> Option<T> {
+        if let (false, Some((width, height))) = (self.in_bounds(point), self.size) {
+            panic!("{:?} is outside the {}x{} grid", point, width, height);
+        }
+        self.cells.insert(point, cell)
+    }
+
+    /// Unsets the cell at the given point, so that it holds the default again, returning the cell
+    /// which was set there.
+    pub fn remove(&mut self, point: Point) -> Option<T> {
+        self.cells.remove(&point)
+    }
+
+    /// Returns every point which has been set along with its cell, in no particular order.
+    pub fn cells(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&point, cell)| (point, cell))
+    }
+}
+
+impl<T: Clone + PartialEq> SparseGrid<T> {
+    /// Makes a map bounded to the same size as `grid`, storing only the cells which differ from
+    /// `default`.
+    pub fn from_grid(grid: &Grid<T>, default: T) -> Self {
+        let mut sparse = SparseGrid::new(Some(default)).bounded(grid.width(), grid.height());
+        for (point, cell) in grid.cells() {
+            if Some(cell) != sparse.default.as_ref() {
+                sparse.cells.insert(point, cell.clone());
+            }
+        }
+        sparse
+    }
+}
+
+impl<T> Cells for SparseGrid<T> {
+    type Cell = T;
+
+    fn get(&self, point: Point) -> Option<&T> {
+        SparseGrid::get(self, point)
+    }
+
+    fn is_finite(&self) -> bool {
+        self.size.is_some() || self.default.is_none()
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (Point, &T)> {
+        SparseGrid::cells(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseGrid;
+    use crate::{Cells, Direction, Grid, Point};
+
+    #[test]
+    fn test_without_default() {
+        let mut grid = SparseGrid::new(None);
+        assert_eq!(grid.insert(Point::new(-5, 3), 'a'), None);
+        assert_eq!(grid.insert(Point::new(-5, 3), 'b'), Some('a'));
+        grid.insert(Point::new(1_000_000, 0), 'c');
+        assert_eq!(grid.get(Point::new(-5, 3)), Some(&'b'));
+        assert_eq!(grid.get(Point::new(0, 0)), None);
+        assert_eq!(
+            grid.step(Point::new(-5, 2), Direction::Right),
+            Some(Point::new(-5, 3))
+        );
+        assert_eq!(grid.step(Point::new(-5, 3), Direction::Right), None);
+        assert_eq!(grid.len(), 2);
+        assert!(grid.is_finite());
+        assert_eq!(grid.remove(Point::new(-5, 3)), Some('b'));
+        assert!(!grid.contains(Point::new(-5, 3)));
+    }
+
+    #[test]
+    fn test_with_default() {
+        let mut grid = SparseGrid::new(Some('.'));
+        grid.insert(Point::new(2, 2), '#');
+        assert_eq!(grid.get(Point::new(2, 2)), Some(&'#'));
+        assert_eq!(grid.get(Point::new(-7, 9_000_000_000)), Some(&'.'));
+        assert!(!grid.is_empty() && grid.size().is_none());
+        assert!(!grid.is_finite());
+        let grid = grid.bounded(3, 4);
+        assert!(grid.is_finite());
+        assert_eq!(grid.get(Point::new(3, 2)), Some(&'.'));
+        assert_eq!(grid.get(Point::new(2, 3)), None);
+        assert_eq!(grid.get(Point::new(-1, 0)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the 3x4 grid")]
+    fn test_insert_out_of_bounds() {
+        SparseGrid::new(Some('.'))
+            .bounded(3, 4)
+            .insert(Point::new(0, 3), '#');
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let dense: Grid = "..#\n#..\n".parse().unwrap();
+        let sparse = SparseGrid::from_grid(&dense, b'.');
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.size(), Some((3, 2)));
+        for row in -1..3 {
+            for col in -1..4 {
+                let point = Point::new(row, col);
+                assert_eq!(sparse.get(point), dense.get(point));
+            }
+        }
+        let mut set: Vec<Point> = sparse.cells().map(|(point, _)| point).collect();
+        set.sort();
+        assert_eq!(set, vec![Point::new(0, 2), Point::new(1, 0)]);
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+/// Calls `f` once and returns its result along with how long it took.
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// How long to spend on each sample when measuring, and on all of a measurement's samples.
+const SAMPLE_TARGET: Duration = Duration::from_millis(20);
+const MEASUREMENT_BUDGET: Duration = Duration::from_millis(500);
+const MIN_SAMPLES: usize = 3;
+const MAX_SAMPLES: usize = 25;
+
+/// Measures how long one call of `f` takes, as the median over several samples. Fast functions
+/// are called many times per sample so the clock's resolution doesn't matter, and slow functions
+/// get fewer samples so the measurement stays within about half a second.
+pub fn measure(mut f: impl FnMut()) -> Duration {
+    let ((), first) = time(&mut f);
+    let iterations =
+        (SAMPLE_TARGET.as_nanos() / first.as_nanos().max(1)).clamp(1, 1_000_000) as u32;
+    let start = Instant::now();
+    let mut samples = Vec::new();
+    while samples.len() < MIN_SAMPLES
+        || (samples.len() < MAX_SAMPLES && start.elapsed() < MEASUREMENT_BUDGET)
+    {
+        let ((), elapsed) = time(|| (0..iterations).for_each(|_| f()));
+        samples.push(elapsed / iterations);
+    }
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+/// A duration formatted with three significant figures in the largest unit that keeps it at
+/// least 1, such as `512 ns`, `3.21 ms` or `1.50 s`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Elapsed(pub Duration);
+
+impl std::fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let nanos = self.0.as_nanos() as f64;
+        let (value, unit) = [(1e9, "s"), (1e6, "ms"), (1e3, "µs")]
+            .into_iter()
+            .find(|&(scale, _)| nanos >= scale)
+            .map_or((nanos, "ns"), |(scale, unit)| (nanos / scale, unit));
+        let decimals = match value {
+            v if unit == "ns" || v >= 100.0 => 0,
+            v if v >= 10.0 => 1,
+            _ => 2,
+        };
+        f.pad(&format!("{:.*} {}", decimals, value, unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Elapsed;
+    use std::time::Duration;
+
+    #[test]
+    fn test_elapsed_display() {
+        assert_eq!(Elapsed(Duration::from_nanos(512)).to_string(), "512 ns");
+        assert_eq!(Elapsed(Duration::from_nanos(1_234)).to_string(), "1.23 µs");
+        assert_eq!(
+            Elapsed(Duration::from_micros(45_678)).to_string(),
+            "45.7 ms"
+        );
+        assert_eq!(Elapsed(Duration::from_millis(999)).to_string(), "999 ms");
+        assert_eq!(Elapsed(Duration::from_millis(1_500)).to_string(), "1.50 s");
+        assert_eq!(Elapsed(Duration::ZERO).to_string(), "0 ns");
+    }
+
+    #[test]
+    fn test_measure() {
+        let mut calls = 0;
+        let per_call = super::measure(|| {
+            calls += 1;
+            std::hint::black_box(calls);
+        });
+        assert!(calls > 3);
+        assert!(per_call < Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,150 @@
+//! Times parsing and each part of every day, on the example input from the puzzle description and
+//! on a generated input about the size of a real one, and prints the results as a Markdown
+//! table.
+//!
+//!     cargo bench -p aoc --bench days -- [--output ../BENCHMARKS.md] [DAY...]
+//!
+//! With `--output` the table is also written to the given file, so it can be committed and
+//! compared across changes. Cargo runs benchmarks from the `aoc` directory, so relative paths
+//! are relative to it.
+
+use aoc_common::timing::measure;
+use aoc_common::{Elapsed, Solution};
+use std::hint::black_box;
+use std::time::Duration;
+
+/// The seed for the generated inputs, so every run benchmarks the same inputs.
+const SEED: u64 = 2024;
+
+struct Row {
+    day: u8,
+    input: String,
+    bytes: usize,
+    parse: Duration,
+    part1: Duration,
+    part2: Duration,
+}
+
+fn bench<S: Solution>(day: u8, name: &str, input: &str) -> Row {
+    let parse = measure(|| {
+        black_box(S::parse(black_box(input.as_bytes())).ok());
+    });
+    let parsed = S::parse(input.as_bytes())
+        .unwrap_or_else(|err| panic!("day {} {} input is malformed: {}", day, name, err));
+    let part1 = measure(|| {
+        black_box(S::part1(black_box(&parsed)));
+    });
+    let part2 = measure(|| {
+        black_box(S::part2(black_box(&parsed)));
+    });
+    Row {
+        day,
+        input: name.to_string(),
+        bytes: input.len(),
+        parse,
+        part1,
+        part2,
+    }
+}
+
+fn bench_day(day: u8) -> Vec<Row> {
+    fn both<S: Solution>(day: u8, example: &str, large: String) -> Vec<Row> {
+        vec![
+            bench::<S>(day, "example", example),
+            bench::<S>(day, "large", &large),
+        ]
+    }
+    match day {
+        1 => both::<day1::Day1>(
+            day,
+            day1::EXAMPLE_INPUT,
+            day1::generator::generate(SEED, 1000),
+        ),
+        2 => both::<day2::Day2>(
+            day,
+            day2::EXAMPLE_INPUT,
+            day2::generator::generate(SEED, 1000),
+        ),
+        3 => both::<day3::Day3>(
+            day,
+            day3::EXAMPLE_INPUT,
+            day3::generator::generate(SEED, 20_000),
+        ),
+        4 => both::<day4::Day4>(
+            day,
+            day4::EXAMPLE_INPUT,
+            day4::generator::generate(SEED, 140),
+        ),
+        5 => both::<day5::Day5>(
+            day,
+            day5::EXAMPLE_INPUT,
+            day5::generator::generate(SEED, 200),
+        ),
+        6 => both::<day6::Day6>(
+            day,
+            day6::EXAMPLE_INPUT,
+            day6::generator::generate(SEED, 130),
+        ),
+        7 => both::<day7::Day7>(
+            day,
+            day7::EXAMPLE_INPUT,
+            day7::generator::generate(SEED, 850),
+        ),
+        _ => panic!("no solution for day {}", day),
+    }
+}
+
+fn table(rows: &[Row]) -> String {
+    let mut out = String::from("| day | input | bytes | parse | part 1 | part 2 |\n");
+    out += "|----:|:------|------:|------:|-------:|-------:|\n";
+    for row in rows {
+        out += &format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            row.day,
+            row.input,
+            row.bytes,
+            Elapsed(row.parse),
+            Elapsed(row.part1),
+            Elapsed(row.part2)
+        );
+    }
+    out
+}
+
+/// The days there are solutions for.
+const DAYS: std::ops::RangeInclusive<u8> = 1..=7;
+
+fn main() {
+    let mut output = None;
+    let mut days = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => output = Some(args.next().expect("--output needs a path")),
+            // `cargo bench` passes `--bench` and any flags meant for other bench harnesses.
+            flag if flag.starts_with("--") => {}
+            day => match day.parse::<u8>() {
+                Ok(day) if DAYS.contains(&day) => days.push(day),
+                _ => {
+                    eprintln!(
+                        "error: `{}` is not a day from {} to {}",
+                        day,
+                        DAYS.start(),
+                        DAYS.end()
+                    );
+                    std::process::exit(2);
+                }
+            },
+        }
+    }
+    if days.is_empty() {
+        days = DAYS.collect();
+    }
+    let rows: Vec<Row> = days.into_iter().flat_map(bench_day).collect();
+    let table = table(&rows);
+    print!("{}", table);
+    if let Some(path) = output {
+        std::fs::write(&path, &table)
+            .unwrap_or_else(|err| panic!("cannot write {}: {}", path, err));
+    }
+}
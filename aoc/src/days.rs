@@ -0,0 +1,219 @@
+use aoc_common::render;
+use aoc_common::timing::time;
+use aoc_common::{Part, Report, Solution};
+use day7::operator::Builtin;
+use day7::Precedence;
+use std::time::Duration;
+
+/// The solution for day 7, with numbers of any size with the `bigint` feature.
+#[cfg(not(feature = "bigint"))]
+type Day7 = day7::Day7;
+#[cfg(feature = "bigint")]
+type Day7 = day7::Calibration<day7::BigUint>;
+
+/// The days which have a solution.
+pub const DAYS: std::ops::RangeInclusive<u8> = 1..=7;
+
+/// One part's answer and how long it took to solve, not counting parsing.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// The answers for one day's input, along with how long parsing took.
+#[derive(Debug)]
+pub struct Solved {
+    pub parse: Duration,
+    pub answers: Vec<Answer>,
+}
+
+/// Parses the input for the given day and returns the answer to each of the requested parts.
+/// Day 7 applies its operators in order of `precedence`; other days only take
+/// `Precedence::LeftToRight`.
+pub fn solve(
+    day: u8,
+    parts: &[Part],
+    precedence: Precedence<Builtin>,
+    r: impl std::io::BufRead,
+) -> Result<Solved, Box<dyn std::error::Error>> {
+    if day != 7 && !matches!(precedence, Precedence::LeftToRight) {
+        return Err(format!("day {} has no operators to give a precedence", day).into());
+    }
+    match day {
+        1 => solve_parts::<day1::Day1>(parts, r),
+        2 => solve_parts::<day2::Day2>(parts, r),
+        3 => solve_parts::<day3::Day3>(parts, r),
+        4 => solve_parts::<day4::Day4>(parts, r),
+        5 => solve_parts::<day5::Day5>(parts, r),
+        6 => solve_parts::<day6::Day6>(parts, r),
+        7 => solve_parts_with::<Day7>(parts, r, |eqs, part| {
+            Day7::solve_with(eqs, part, precedence)
+        }),
+        _ => Err(format!("no solution for day {}", day).into()),
+    }
+}
+
+/// Parses the input for the given day and draws a picture of it, for the days which have one.
+pub fn render(
+    day: u8,
+    r: impl std::io::BufRead,
+    format: render::Format,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    match day {
+        4 => Ok(day4::picture(&day4::Day4::parse(r)?).draw(format)),
+        6 => Ok(day6::picture(&day6::Day6::parse(r)?).draw(format)),
+        _ => Err(format!("no picture for day {}", day).into()),
+    }
+}
+
+/// Parses the input for the given day and returns, for each of the requested parts, a witness of
+/// how each line which counts towards the answer does so, for the days which have them. Day 7's
+/// witnesses are equations with their operators filled in, applied in order of `precedence`.
+pub fn witnesses(
+    day: u8,
+    parts: &[Part],
+    precedence: Precedence<Builtin>,
+    r: impl std::io::BufRead,
+) -> Result<Vec<(Part, String)>, Box<dyn std::error::Error>> {
+    match day {
+        7 => {
+            let eqs = Day7::parse(r)?;
+            Ok(parts
+                .iter()
+                .flat_map(|&part| {
+                    day7::witnesses(&eqs, day7::operator::for_part(part), precedence)
+                        .map(move |witness| (part, witness.to_string()))
+                })
+                .collect())
+        }
+        _ => Err(format!("no witnesses for day {}", day).into()),
+    }
+}
+
+fn solve_parts<S: Solution>(
+    parts: &[Part],
+    r: impl std::io::BufRead,
+) -> Result<Solved, Box<dyn std::error::Error>> {
+    solve_parts_with::<S>(parts, r, S::solve)
+}
+
+/// Like `solve_parts`, but solves each part with `solve`.
+fn solve_parts_with<S: Solution>(
+    parts: &[Part],
+    r: impl std::io::BufRead,
+    solve: impl Fn(&S::Input, Part) -> S::Answer,
+) -> Result<Solved, Box<dyn std::error::Error>> {
+    let (input, parse) = time(|| S::parse(r));
+    let input = input?;
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let (answer, elapsed) = time(|| solve(&input, part));
+            Ok(Answer {
+                part,
+                answer: answer.report()?,
+                elapsed,
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Solved { parse, answers })
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::render::Format;
+    use aoc_common::Part;
+    use day7::Precedence;
+
+    #[test]
+    fn test_solve() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        let answers = |parts| {
+            let solved = super::solve(1, parts, Precedence::LeftToRight, input.as_bytes()).unwrap();
+            solved
+                .answers
+                .into_iter()
+                .map(|answer| (answer.part, answer.answer))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            answers(&Part::BOTH),
+            vec![(Part::One, "11".to_string()), (Part::Two, "31".to_string())]
+        );
+        assert_eq!(answers(&[Part::Two]), vec![(Part::Two, "31".to_string())]);
+    }
+
+    #[test]
+    fn test_solve_precedence() {
+        let input = "1161: 81 40 27\n3267: 81 40 27\n190: 10 19\n";
+        let answer = |precedence| {
+            let solved = super::solve(7, &[Part::One], precedence, input.as_bytes()).unwrap();
+            solved.answers[0].answer.clone()
+        };
+        assert_eq!(answer(Precedence::LeftToRight), "3457");
+        assert_eq!(answer(Precedence::Standard), "4618");
+        let err = super::solve(1, &Part::BOTH, Precedence::Standard, "".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "day 1 has no operators to give a precedence"
+        );
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_solve_overflow() {
+        let input = format!("{}: {} 1\n1: 1\n", usize::MAX, usize::MAX);
+        let err = super::solve(7, &[Part::One], Precedence::LeftToRight, input.as_bytes());
+        let err = err.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the sum of the test values of the true equations does not fit in usize"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let picture = super::render(6, "..\n^.\n".as_bytes(), Format::Ansi).unwrap();
+        assert_eq!(
+            picture,
+            "\x1b[30;43m.\x1b[0m.\n\x1b[1;31m↑\x1b[0m.\n".as_bytes()
+        );
+        assert!(super::render(1, "".as_bytes(), Format::Ansi).is_err());
+    }
+
+    #[test]
+    fn test_witnesses() {
+        let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n";
+        let witness = |part, witness: &str| (part, witness.to_string());
+        assert_eq!(
+            super::witnesses(7, &Part::BOTH, Precedence::LeftToRight, input.as_bytes()).unwrap(),
+            [
+                witness(Part::One, "10 * 19 = 190"),
+                witness(Part::One, "(81 + 40) * 27 = 3267"),
+                witness(Part::Two, "10 * 19 = 190"),
+                witness(Part::Two, "(81 + 40) * 27 = 3267"),
+                witness(Part::Two, "15 || 6 = 156"),
+            ]
+        );
+        let standard = super::witnesses(
+            7,
+            &[Part::One],
+            Precedence::Standard,
+            "1161: 81 40 27\n".as_bytes(),
+        );
+        assert_eq!(
+            standard.unwrap(),
+            [witness(Part::One, "81 + 40 * 27 = 1161")]
+        );
+        assert!(super::witnesses(1, &Part::BOTH, Precedence::LeftToRight, "".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_solve_unknown_day() {
+        let solve = |day| super::solve(day, &Part::BOTH, Precedence::LeftToRight, "".as_bytes());
+        assert!(solve(0).is_err());
+        assert!(solve(26).is_err());
+    }
+}
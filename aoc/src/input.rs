@@ -0,0 +1,74 @@
+use crate::openpgp;
+use aoc_common::input::{map_file, Bytes};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// The environment variable holding the passphrase for encrypted puzzle inputs. If it is unset,
+/// the passphrase is prompted for on the terminal instead.
+pub const PASSPHRASE_VAR: &str = "AOC_PASSPHRASE";
+
+/// Reads puzzle inputs, memory-mapping plain files and decrypting any OpenPGP-encrypted (`.gpg`)
+/// files in memory. The passphrase is only asked for once, when the first encrypted file is read.
+#[derive(Default)]
+pub struct Inputs {
+    passphrase: Option<Zeroizing<String>>,
+}
+
+impl Inputs {
+    #[cfg(test)]
+    pub fn with_passphrase(passphrase: &str) -> Self {
+        Inputs {
+            passphrase: Some(Zeroizing::new(passphrase.into())),
+        }
+    }
+
+    pub fn read(&mut self, path: &Path) -> Result<Bytes, Box<dyn std::error::Error>> {
+        let contents =
+            map_file(path).map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+        if !is_encrypted(path) {
+            return Ok(contents);
+        }
+        let passphrase = self.passphrase()?;
+        let plaintext = openpgp::decrypt(&contents, passphrase.as_bytes())
+            .map_err(|err| format!("cannot decrypt {}: {}", path.display(), err))?;
+        Ok(plaintext.into())
+    }
+
+    fn passphrase(&mut self) -> std::io::Result<&str> {
+        if self.passphrase.is_none() {
+            let passphrase = match std::env::var(PASSPHRASE_VAR) {
+                Ok(passphrase) => passphrase,
+                Err(_) => rpassword::prompt_password("Passphrase for puzzle inputs: ")?,
+            };
+            self.passphrase = Some(Zeroizing::new(passphrase));
+        }
+        Ok(self.passphrase.as_ref().unwrap())
+    }
+}
+
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "gpg" || ext == "pgp")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    #[test]
+    fn test_is_encrypted() {
+        assert!(super::is_encrypted(Path::new("day1/input.txt.gpg")));
+        assert!(super::is_encrypted(Path::new("input.pgp")));
+        assert!(!super::is_encrypted(Path::new("day1/input.txt")));
+        assert!(!super::is_encrypted(Path::new("gpg")));
+    }
+
+    #[test]
+    fn test_read() {
+        let mut inputs = super::Inputs::with_passphrase("correct-horse");
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let plaintext = inputs.read(&dir.join("example.txt")).unwrap();
+        let decrypted = inputs.read(&dir.join("aes256-sha512-zip.txt.gpg")).unwrap();
+        assert_eq!(*plaintext, *decrypted);
+    }
+}
@@ -0,0 +1,262 @@
+use aoc_common::output::{Format, Record};
+use aoc_common::render;
+use aoc_common::{Elapsed, Part};
+use clap::{Parser, Subcommand};
+use day7::operator::Builtin;
+use day7::Precedence;
+use input::Inputs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+mod days;
+mod input;
+mod openpgp;
+mod verify;
+
+#[derive(Parser)]
+#[command(about = "Run the Advent of Code 2024 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one day's puzzle for the given input
+    Run {
+        /// The day to run
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// The part to run; both parts are run if omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// The puzzle input, decrypted first if it ends in `.gpg`; read from stdin if omitted
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Also report how long parsing and each part took, on stderr
+        #[arg(long)]
+        time: bool,
+        /// Print answers as `text`, or as `json` records with the time each part took
+        #[arg(long, default_value = "text")]
+        format: Format,
+        /// Apply day 7's operators `left-to-right`, as the puzzle does, or with `standard`
+        /// precedence, multiplication before addition
+        #[arg(long, default_value = "left-to-right")]
+        precedence: Precedence<Builtin>,
+    },
+    /// Draw a picture of what a day's solver does with the given input
+    ///
+    /// Day 4 highlights every `XMAS`, and day 6 the guard's path.
+    Render {
+        /// The day to draw
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// The puzzle input, decrypted first if it ends in `.gpg`; read from stdin if omitted
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Draw as `ansi` coloured text, `svg` or `png`
+        #[arg(long, default_value = "ansi")]
+        format: render::Format,
+        /// Where to write the picture; written to stdout if omitted
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Print how each line of the input counts towards a part's answer
+    ///
+    /// Day 7 prints each equation which can be made true, with the operators which make it true.
+    Witnesses {
+        /// The day to explain
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// The part to explain; both parts are explained if omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// The puzzle input, decrypted first if it ends in `.gpg`; read from stdin if omitted
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Apply day 7's operators `left-to-right`, as the puzzle does, or with `standard`
+        /// precedence, multiplication before addition
+        #[arg(long, default_value = "left-to-right")]
+        precedence: Precedence<Builtin>,
+    },
+    /// Check every day's answers against the answers recorded next to its input
+    ///
+    /// Each `dayN` directory holds `input.txt.gpg` (or `input.txt`) and `answers.txt.gpg` (or
+    /// `answers.txt`) containing the accepted output of `aoc run` for that input. To record the
+    /// answers without writing them to disk in plaintext:
+    ///
+    ///     aoc run --day N --input dayN/input.txt.gpg | gpg -c -o dayN/answers.txt.gpg
+    Verify {
+        /// Check only this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// The directory holding the `dayN` directories
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Run {
+            day,
+            part,
+            input,
+            time,
+            format,
+            precedence,
+        } => run(day, part, input, time, format, precedence),
+        Command::Render {
+            day,
+            input,
+            format,
+            output,
+        } => render(day, input, format, output),
+        Command::Witnesses {
+            day,
+            part,
+            input,
+            precedence,
+        } => witnesses(day, part, input, precedence),
+        Command::Verify { day, root } => verify(day, root),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(
+    day: u8,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    time: bool,
+    format: Format,
+    precedence: Precedence<Builtin>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let parts = match part.and_then(Part::from_number) {
+        Some(part) => vec![part],
+        None => Part::BOTH.to_vec(),
+    };
+    let solved = match input {
+        Some(path) => {
+            let contents = Inputs::default().read(&path)?;
+            days::solve(day, &parts, precedence, &contents[..])?
+        }
+        None => days::solve(day, &parts, precedence, std::io::stdin().lock())?,
+    };
+    if time {
+        eprintln!("day {} parse took {}", day, Elapsed(solved.parse));
+    }
+    for answer in solved.answers {
+        let record = Record {
+            day,
+            part: answer.part,
+            answer: answer.answer,
+            elapsed: answer.elapsed,
+        };
+        println!("{}", record.format(format));
+        if time {
+            eprintln!(
+                "day {} part {} took {}",
+                day,
+                answer.part,
+                Elapsed(answer.elapsed)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn render(
+    day: u8,
+    input: Option<PathBuf>,
+    format: render::Format,
+    output: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let picture = match input {
+        Some(path) => {
+            let contents = Inputs::default().read(&path)?;
+            days::render(day, &contents[..], format)?
+        }
+        None => days::render(day, std::io::stdin().lock(), format)?,
+    };
+    match output {
+        Some(path) => std::fs::write(&path, picture)
+            .map_err(|err| format!("cannot write {}: {}", path.display(), err))?,
+        None => std::io::stdout().lock().write_all(&picture)?,
+    }
+    Ok(())
+}
+
+fn witnesses(
+    day: u8,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    precedence: Precedence<Builtin>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let parts = match part.and_then(Part::from_number) {
+        Some(part) => vec![part],
+        None => Part::BOTH.to_vec(),
+    };
+    let witnesses = match input {
+        Some(path) => {
+            let contents = Inputs::default().read(&path)?;
+            days::witnesses(day, &parts, precedence, &contents[..])?
+        }
+        None => days::witnesses(day, &parts, precedence, std::io::stdin().lock())?,
+    };
+    for (part, witness) in witnesses {
+        println!("part {}: {}", part, witness);
+    }
+    Ok(())
+}
+
+fn verify(day: Option<u8>, root: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    let days = match day {
+        Some(day) => day..=day,
+        None => days::DAYS,
+    };
+    let outcomes = verify::verify_days(&mut Inputs::default(), &root, days);
+    print!("{}", verify::table(&outcomes));
+    let not_passed = outcomes
+        .iter()
+        .filter(|outcome| outcome.status != verify::Status::Pass)
+        .count();
+    if not_passed > 0 {
+        return Err(format!("{} of {} answers did not pass", not_passed, outcomes.len()).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cli, Command};
+    use clap::Parser;
+    use day7::Precedence;
+
+    #[test]
+    fn test_run_precedence() {
+        let precedence = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["aoc", "run", "--day", "7"], args].concat());
+            match cli.map(|cli| cli.command) {
+                Ok(Command::Run { precedence, .. }) => Ok(precedence),
+                Ok(_) => panic!("expected the run command"),
+                Err(err) => Err(err.kind()),
+            }
+        };
+        assert!(matches!(precedence(&[]), Ok(Precedence::LeftToRight)));
+        assert!(matches!(
+            precedence(&["--precedence", "standard"]),
+            Ok(Precedence::Standard)
+        ));
+        assert_eq!(
+            precedence(&["--precedence", "right-to-left"]).unwrap_err(),
+            clap::error::ErrorKind::ValueValidation
+        );
+    }
+}
@@ -0,0 +1,569 @@
+//! Just enough of OpenPGP (RFC 4880) to decrypt the passphrase-protected messages which
+//! `gpg --symmetric` produces, which is how the puzzle inputs are stored in this repository.
+//!
+//! Only symmetrically encrypted, integrity protected data (SEIPD version 1) is supported, with
+//! CAST5 or AES keys derived from the passphrase by any of the standard string-to-key methods,
+//! and optionally compressed with ZIP or ZLIB. Everything is decrypted in memory.
+
+use cipher::{BlockEncrypt, KeyInit};
+use sha1::Digest;
+use zeroize::Zeroizing;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The message is not valid OpenPGP.
+    Malformed(&'static str),
+    /// The message uses a packet type or algorithm which is not supported here.
+    Unsupported(String),
+    /// The passphrase does not decrypt the message.
+    BadPassphrase,
+    /// The decrypted message does not match its modification detection code.
+    Tampered,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Malformed(what) => write!(f, "malformed OpenPGP message: {}", what),
+            Error::Unsupported(what) => write!(f, "unsupported OpenPGP feature: {}", what),
+            Error::BadPassphrase => write!(f, "wrong passphrase"),
+            Error::Tampered => write!(f, "message failed its integrity check"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+const TAG_PUBLIC_KEY: u8 = 1;
+const TAG_SIGNATURE: u8 = 2;
+const TAG_SYMMETRIC_KEY: u8 = 3;
+const TAG_ONE_PASS_SIGNATURE: u8 = 4;
+const TAG_COMPRESSED: u8 = 8;
+const TAG_SYMMETRICALLY_ENCRYPTED: u8 = 9;
+const TAG_MARKER: u8 = 10;
+const TAG_LITERAL: u8 = 11;
+const TAG_INTEGRITY_PROTECTED: u8 = 18;
+
+/// Compressed packets may nest, but there is no reason for a real message to nest deeply.
+const MAX_NESTING: usize = 8;
+
+/// The most a message may decompress to, all compressed packets together, so a small crafted
+/// message can't exhaust memory. Real puzzle inputs are a few tens of kilobytes.
+const MAX_DECOMPRESSED: usize = 256 << 20;
+
+/// Decrypts a binary (non-armored) OpenPGP message encrypted with the given passphrase, and
+/// returns the contents of its literal data packet.
+pub fn decrypt(message: &[u8], passphrase: &[u8]) -> Result<Vec<u8>, Error> {
+    if message.starts_with(b"-----BEGIN PGP") {
+        return Err(Error::Unsupported("ASCII-armored messages".into()));
+    }
+    let mut session_keys = Vec::new();
+    // Why the last key packet we could not use was skipped, reported if none is usable.
+    let mut skipped = None;
+    let mut reader = Reader::new(message);
+    while !reader.is_empty() {
+        let packet = read_packet(&mut reader)?;
+        match packet.tag {
+            // A message may carry one key packet per passphrase, and only one of them need match
+            // ours, so key packets for other passphrases or in unsupported forms are skipped.
+            TAG_SYMMETRIC_KEY => match session_key(&packet.body, passphrase) {
+                Ok(key) => session_keys.push(key),
+                Err(err @ (Error::BadPassphrase | Error::Unsupported(_))) => skipped = Some(err),
+                Err(err) => return Err(err),
+            },
+            TAG_INTEGRITY_PROTECTED => {
+                let mut result = Err(skipped.unwrap_or(Error::BadPassphrase));
+                for (algorithm, key) in &session_keys {
+                    result = decrypt_integrity_protected(&packet.body, *algorithm, key);
+                    if result != Err(Error::BadPassphrase) {
+                        break;
+                    }
+                }
+                return literal_data(&result?, 0, MAX_DECOMPRESSED);
+            }
+            TAG_SYMMETRICALLY_ENCRYPTED => {
+                return Err(Error::Unsupported(
+                    "encrypted data without integrity protection".into(),
+                ))
+            }
+            // Session keys encrypted to a public key are of no use with a passphrase.
+            TAG_PUBLIC_KEY | TAG_MARKER => continue,
+            tag => return Err(Error::Unsupported(format!("packet type {}", tag))),
+        }
+    }
+    Err(Error::Malformed("no encrypted data"))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn be_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if n > self.data.len() {
+            return Err(Error::Malformed("unexpected end of data"));
+        }
+        let (taken, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+}
+
+struct Packet {
+    tag: u8,
+    body: Vec<u8>,
+}
+
+fn read_packet(r: &mut Reader) -> Result<Packet, Error> {
+    let ctb = r.byte()?;
+    if ctb & 0x80 == 0 {
+        return Err(Error::Malformed("invalid packet header"));
+    }
+    if ctb & 0x40 == 0 {
+        // Old format: the tag and the size of the length field share the header byte.
+        let tag = (ctb >> 2) & 0x0f;
+        let len = match ctb & 0x03 {
+            0 => r.byte()? as usize,
+            1 => u16::from_be_bytes([r.byte()?, r.byte()?]) as usize,
+            2 => r.be_u32()? as usize,
+            _ => r.data.len(), // indeterminate: the packet runs to the end of the data
+        };
+        let body = r.take(len)?.to_vec();
+        return Ok(Packet { tag, body });
+    }
+    // New format: the body may be split into several partial chunks.
+    let tag = ctb & 0x3f;
+    let mut body = Vec::new();
+    loop {
+        let first = r.byte()?;
+        let len = match first {
+            0..=191 => first as usize,
+            192..=223 => ((first as usize - 192) << 8) + r.byte()? as usize + 192,
+            224..=254 => {
+                body.extend_from_slice(r.take(1 << (first & 0x1f))?);
+                continue;
+            }
+            255 => r.be_u32()? as usize,
+        };
+        body.extend_from_slice(r.take(len)?);
+        return Ok(Packet { tag, body });
+    }
+}
+
+/// A string-to-key specifier, describing how a passphrase is turned into a key.
+enum S2k<'a> {
+    Simple {
+        hash: u8,
+    },
+    Salted {
+        hash: u8,
+        salt: &'a [u8],
+    },
+    Iterated {
+        hash: u8,
+        salt: &'a [u8],
+        count: usize,
+    },
+}
+
+impl<'a> S2k<'a> {
+    fn read(r: &mut Reader<'a>) -> Result<Self, Error> {
+        match r.byte()? {
+            0 => Ok(S2k::Simple { hash: r.byte()? }),
+            1 => Ok(S2k::Salted {
+                hash: r.byte()?,
+                salt: r.take(8)?,
+            }),
+            3 => {
+                let hash = r.byte()?;
+                let salt = r.take(8)?;
+                let c = r.byte()? as usize;
+                let count = (16 + (c & 15)) << ((c >> 4) + 6);
+                Ok(S2k::Iterated { hash, salt, count })
+            }
+            mode => Err(Error::Unsupported(format!("string-to-key mode {}", mode))),
+        }
+    }
+
+    fn derive_key(&self, passphrase: &[u8], len: usize) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let (hash, salt, count) = match *self {
+            S2k::Simple { hash } => (hash, &[][..], 0),
+            S2k::Salted { hash, salt } => (hash, salt, 0),
+            S2k::Iterated { hash, salt, count } => (hash, salt, count),
+        };
+        // Keys longer than the digest are built from further hash contexts, each preloaded with
+        // one more zero byte than the last.
+        let mut key = Zeroizing::new(Vec::with_capacity(len));
+        let mut preload = 0;
+        while key.len() < len {
+            let digest = match hash {
+                1 => s2k_digest::<md5::Md5>(preload, salt, passphrase, count),
+                2 => s2k_digest::<sha1::Sha1>(preload, salt, passphrase, count),
+                8 => s2k_digest::<sha2::Sha256>(preload, salt, passphrase, count),
+                9 => s2k_digest::<sha2::Sha384>(preload, salt, passphrase, count),
+                10 => s2k_digest::<sha2::Sha512>(preload, salt, passphrase, count),
+                11 => s2k_digest::<sha2::Sha224>(preload, salt, passphrase, count),
+                _ => return Err(Error::Unsupported(format!("hash algorithm {}", hash))),
+            };
+            key.extend_from_slice(&digest);
+            preload += 1;
+        }
+        key.truncate(len);
+        Ok(key)
+    }
+}
+
+/// Hashes the salt and passphrase, repeated until `count` bytes have been hashed (but always at
+/// least once).
+fn s2k_digest<D: Digest>(
+    preload: usize,
+    salt: &[u8],
+    passphrase: &[u8],
+    count: usize,
+) -> Zeroizing<Vec<u8>> {
+    let mut hasher = D::new();
+    hasher.update(vec![0; preload]);
+    let unit = Zeroizing::new([salt, passphrase].concat());
+    let mut remaining = count.max(unit.len());
+    if !unit.is_empty() {
+        // Hashing in large runs rather than one salt and passphrase at a time keeps the default
+        // iteration count (tens of megabytes) fast.
+        let mut run = Zeroizing::new(Vec::with_capacity(unit.len() * (65536 / unit.len() + 1)));
+        while run.len() < 65536 {
+            run.extend_from_slice(&unit);
+        }
+        while remaining > 0 {
+            let n = remaining.min(run.len());
+            hasher.update(&run[..n]);
+            remaining -= n;
+        }
+    }
+    Zeroizing::new(hasher.finalize().to_vec())
+}
+
+enum Cipher {
+    Cast5(cast5::Cast5),
+    Aes128(aes::Aes128),
+    Aes192(aes::Aes192),
+    Aes256(aes::Aes256),
+}
+
+impl Cipher {
+    fn key_len(algorithm: u8) -> Result<usize, Error> {
+        match algorithm {
+            3 | 7 => Ok(16),
+            8 => Ok(24),
+            9 => Ok(32),
+            _ => Err(Error::Unsupported(format!(
+                "symmetric algorithm {}",
+                algorithm
+            ))),
+        }
+    }
+
+    fn new(algorithm: u8, key: &[u8]) -> Result<Self, Error> {
+        let bad_key = |_| Error::Malformed("wrong key length");
+        match algorithm {
+            3 => cast5::Cast5::new_from_slice(key)
+                .map(Cipher::Cast5)
+                .map_err(bad_key),
+            7 => aes::Aes128::new_from_slice(key)
+                .map(Cipher::Aes128)
+                .map_err(bad_key),
+            8 => aes::Aes192::new_from_slice(key)
+                .map(Cipher::Aes192)
+                .map_err(bad_key),
+            9 => aes::Aes256::new_from_slice(key)
+                .map(Cipher::Aes256)
+                .map_err(bad_key),
+            _ => Err(Error::Unsupported(format!(
+                "symmetric algorithm {}",
+                algorithm
+            ))),
+        }
+    }
+
+    fn block_size(&self) -> usize {
+        match self {
+            Cipher::Cast5(_) => 8,
+            _ => 16,
+        }
+    }
+
+    fn encrypt_block(&self, block: &mut [u8]) {
+        match self {
+            Cipher::Cast5(c) => c.encrypt_block(block.into()),
+            Cipher::Aes128(c) => c.encrypt_block(block.into()),
+            Cipher::Aes192(c) => c.encrypt_block(block.into()),
+            Cipher::Aes256(c) => c.encrypt_block(block.into()),
+        }
+    }
+
+    /// Decrypts `data` in place in cipher feedback mode, starting from an all-zero IV.
+    fn cfb_decrypt(&self, data: &mut [u8]) {
+        let mut register = vec![0; self.block_size()];
+        let mut keystream = vec![0; self.block_size()];
+        for chunk in data.chunks_mut(self.block_size()) {
+            keystream.copy_from_slice(&register);
+            self.encrypt_block(&mut keystream);
+            register[..chunk.len()].copy_from_slice(chunk);
+            chunk
+                .iter_mut()
+                .zip(&keystream)
+                .for_each(|(byte, key)| *byte ^= key);
+        }
+    }
+}
+
+/// Returns the algorithm and key which a symmetric-key encrypted session key packet yields for
+/// the given passphrase.
+fn session_key(body: &[u8], passphrase: &[u8]) -> Result<(u8, Zeroizing<Vec<u8>>), Error> {
+    let mut r = Reader::new(body);
+    let version = r.byte()?;
+    if version != 4 {
+        return Err(Error::Unsupported(format!(
+            "symmetric-key packet version {}",
+            version
+        )));
+    }
+    let algorithm = r.byte()?;
+    let key = S2k::read(&mut r)?.derive_key(passphrase, Cipher::key_len(algorithm)?)?;
+    if r.is_empty() {
+        // The derived key is the session key itself.
+        return Ok((algorithm, key));
+    }
+    let mut encrypted = Zeroizing::new(r.rest().to_vec());
+    Cipher::new(algorithm, &key)?.cfb_decrypt(&mut encrypted);
+    let (&session_algorithm, session_key) = encrypted
+        .split_first()
+        .ok_or(Error::Malformed("empty session key"))?;
+    // With the wrong passphrase, the algorithm and key length are garbage.
+    match Cipher::key_len(session_algorithm) {
+        Ok(len) if len == session_key.len() => {
+            Ok((session_algorithm, Zeroizing::new(session_key.to_vec())))
+        }
+        _ => Err(Error::BadPassphrase),
+    }
+}
+
+/// Decrypts the body of a symmetrically encrypted integrity protected data packet and returns
+/// the packets it contains.
+fn decrypt_integrity_protected(body: &[u8], algorithm: u8, key: &[u8]) -> Result<Vec<u8>, Error> {
+    let (&version, ciphertext) = body
+        .split_first()
+        .ok_or(Error::Malformed("empty encrypted data"))?;
+    if version != 1 {
+        return Err(Error::Unsupported(format!(
+            "encrypted data version {}",
+            version
+        )));
+    }
+    let cipher = Cipher::new(algorithm, key)?;
+    let bs = cipher.block_size();
+    // The plaintext is a random block, a repeat of its last two bytes, the packets, and finally a
+    // modification detection code packet holding the SHA-1 of everything before it.
+    if ciphertext.len() < bs + 2 + 22 {
+        return Err(Error::Malformed("encrypted data too short"));
+    }
+    let mut plaintext = ciphertext.to_vec();
+    cipher.cfb_decrypt(&mut plaintext);
+    if plaintext[bs - 2..bs] != plaintext[bs..bs + 2] {
+        return Err(Error::BadPassphrase);
+    }
+    let (hashed, mdc) = plaintext.split_at(plaintext.len() - 20);
+    if !hashed.ends_with(&[0xd3, 0x14]) || sha1::Sha1::digest(hashed)[..] != mdc[..] {
+        return Err(Error::Tampered);
+    }
+    Ok(hashed[bs + 2..hashed.len() - 2].to_vec())
+}
+
+/// Finds the literal data packet among the given packets, decompressing as needed, to no more
+/// than `limit` bytes in all.
+fn literal_data(packets: &[u8], depth: usize, limit: usize) -> Result<Vec<u8>, Error> {
+    if depth > MAX_NESTING {
+        return Err(Error::Malformed("compressed packets nested too deeply"));
+    }
+    let mut reader = Reader::new(packets);
+    while !reader.is_empty() {
+        let packet = read_packet(&mut reader)?;
+        match packet.tag {
+            TAG_COMPRESSED => {
+                let packets = decompress(&packet.body, limit)?;
+                return literal_data(&packets, depth + 1, limit - packets.len());
+            }
+            TAG_LITERAL => {
+                let mut r = Reader::new(&packet.body);
+                let _format = r.byte()?;
+                let name_len = r.byte()? as usize;
+                let _name = r.take(name_len)?;
+                let _date = r.be_u32()?;
+                return Ok(r.rest().to_vec());
+            }
+            TAG_SIGNATURE | TAG_ONE_PASS_SIGNATURE | TAG_MARKER => continue,
+            tag => return Err(Error::Unsupported(format!("packet type {}", tag))),
+        }
+    }
+    Err(Error::Malformed("no literal data"))
+}
+
+/// Decompresses the body of a compressed data packet, failing if it would be more than `limit`
+/// bytes.
+fn decompress(body: &[u8], limit: usize) -> Result<Vec<u8>, Error> {
+    use miniz_oxide::inflate::{self, DecompressError, TINFLStatus};
+    let (&algorithm, data) = body
+        .split_first()
+        .ok_or(Error::Malformed("empty compressed data"))?;
+    let too_large =
+        || Error::Unsupported(format!("more than {} bytes of decompressed data", limit));
+    let failed = |err: DecompressError| match err.status {
+        TINFLStatus::HasMoreOutput => too_large(),
+        _ => Error::Malformed("corrupt compressed data"),
+    };
+    match algorithm {
+        0 if data.len() > limit => Err(too_large()),
+        0 => Ok(data.to_vec()),
+        1 => inflate::decompress_to_vec_with_limit(data, limit).map_err(failed),
+        2 => inflate::decompress_to_vec_zlib_with_limit(data, limit).map_err(failed),
+        3 => Err(Error::Unsupported("BZip2 compression".into())),
+        _ => Err(Error::Unsupported(format!(
+            "compression algorithm {}",
+            algorithm
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    const PASSPHRASE: &[u8] = b"correct-horse";
+    const EXAMPLE: &[u8] = include_bytes!("../testdata/example.txt");
+
+    #[test]
+    fn test_decrypt() {
+        let messages: [&[u8]; 4] = [
+            include_bytes!("../testdata/aes256-sha512-zip.txt.gpg"),
+            include_bytes!("../testdata/aes128-sha256-zlib.txt.gpg"),
+            include_bytes!("../testdata/aes192-simple-uncompressed.txt.gpg"),
+            include_bytes!("../testdata/cast5-salted-uncompressed.txt.gpg"),
+        ];
+        for message in messages {
+            assert_eq!(super::decrypt(message, PASSPHRASE).unwrap(), EXAMPLE);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_partial_lengths() {
+        let message = include_bytes!("../testdata/large.txt.gpg");
+        let expected = include_bytes!("../testdata/large.txt");
+        assert_eq!(super::decrypt(message, PASSPHRASE).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_decrypt_wrong_passphrase() {
+        let message = include_bytes!("../testdata/aes256-sha512-zip.txt.gpg");
+        assert_eq!(
+            super::decrypt(message, b"battery-staple"),
+            Err(Error::BadPassphrase)
+        );
+    }
+
+    #[test]
+    fn test_decrypt_skips_unsupported_key_packets() {
+        let message = include_bytes!("../testdata/aes256-sha512-zip.txt.gpg");
+        // Key packets with a GnuPG dummy string-to-key, and with the IDEA cipher.
+        let unsupported: &[u8] = &[
+            0xc3, 4, 4, 9, 101, 2, //
+            0xc3, 13, 4, 1, 3, 2, 0, 1, 2, 3, 4, 5, 6, 7, 0x60,
+        ];
+        assert_eq!(
+            super::decrypt(&[unsupported, message].concat(), PASSPHRASE).unwrap(),
+            EXAMPLE
+        );
+        // Without the usable key packet, the reason the others were skipped is reported.
+        let mut reader = super::Reader::new(message);
+        super::read_packet(&mut reader).unwrap();
+        assert!(matches!(
+            super::decrypt(&[unsupported, reader.rest()].concat(), PASSPHRASE),
+            Err(Error::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_decrypt_tampered() {
+        let mut message = include_bytes!("../testdata/aes192-simple-uncompressed.txt.gpg").to_vec();
+        // Flip a bit in the final block, past the quick check bytes.
+        let last = message.len() - 1;
+        message[last] ^= 1;
+        assert_eq!(super::decrypt(&message, PASSPHRASE), Err(Error::Tampered));
+    }
+
+    #[test]
+    fn test_decrypt_malformed() {
+        assert!(matches!(
+            super::decrypt(b"", PASSPHRASE),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            super::decrypt(b"3   4\n", PASSPHRASE),
+            Err(Error::Malformed(_))
+        ));
+        let message = include_bytes!("../testdata/aes256-sha512-zip.txt.gpg");
+        assert!(matches!(
+            super::decrypt(&message[..40], PASSPHRASE),
+            Err(Error::Malformed(_))
+        ));
+        assert!(matches!(
+            super::decrypt(b"-----BEGIN PGP MESSAGE-----\n", PASSPHRASE),
+            Err(Error::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_decompress_limit() {
+        let zeros = [0; 4096];
+        let mut body = vec![1];
+        body.extend(miniz_oxide::deflate::compress_to_vec(&zeros, 6));
+        assert_eq!(super::decompress(&body, 4096).unwrap(), zeros);
+        assert!(matches!(
+            super::decompress(&body, 4095),
+            Err(Error::Unsupported(_))
+        ));
+        body[1] ^= 0xff;
+        assert!(matches!(
+            super::decompress(&body, 4096),
+            Err(Error::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_s2k_count() {
+        let mut r = super::Reader::new(&[3, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0xff]);
+        let Ok(super::S2k::Iterated { hash, count, .. }) = super::S2k::read(&mut r) else {
+            panic!("expected iterated and salted S2K");
+        };
+        assert_eq!(hash, 10);
+        assert_eq!(count, 65011712);
+    }
+}
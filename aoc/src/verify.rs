@@ -0,0 +1,285 @@
+use crate::days;
+use crate::input::Inputs;
+use aoc_common::Part;
+use day7::Precedence;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// How a day's answer compares to the accepted answer recorded for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The answer matches the recorded answer.
+    Pass,
+    /// No answer could be produced or compared: the input or the recorded answers are missing or
+    /// unreadable.
+    Fail,
+    /// The answer differs from the recorded answer.
+    Changed,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Changed => "changed",
+        })
+    }
+}
+
+/// The outcome of checking one part of one day.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: Part,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    pub status: Status,
+    /// Why the check failed, if it did.
+    pub note: String,
+}
+
+/// Finds `<dir>/<stem>.txt.gpg`, falling back to the plaintext `<dir>/<stem>.txt`.
+pub fn find_file(dir: &Path, stem: &str) -> Option<PathBuf> {
+    [format!("{}.txt.gpg", stem), format!("{}.txt", stem)]
+        .into_iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Parses a recorded answers file, which holds the output of `aoc run` for the day: one
+/// `day N part P: ANSWER` line per part.
+pub fn parse_answers(s: &str) -> Result<BTreeMap<Part, String>, String> {
+    let mut answers = BTreeMap::new();
+    for line in s.lines().filter(|line| !line.trim().is_empty()) {
+        let parsed = line.split_once(':').and_then(|(label, answer)| {
+            let mut words = label.split_whitespace();
+            let part = match (words.next(), words.next(), words.next(), words.next()) {
+                (Some("day"), Some(_), Some("part"), Some(part)) => part.parse().ok(),
+                _ => None,
+            }
+            .and_then(Part::from_number)?;
+            Some((part, answer.trim().to_string()))
+        });
+        let Some((part, answer)) = parsed else {
+            return Err(format!("malformed answer line `{}`", line));
+        };
+        answers.insert(part, answer);
+    }
+    Ok(answers)
+}
+
+/// Runs the given day against its input in `<root>/dayN/` and compares each part's answer with
+/// the answers recorded alongside it.
+pub fn verify_day(inputs: &mut Inputs, root: &Path, day: u8) -> Vec<Outcome> {
+    let fail = |note: String| {
+        Part::BOTH
+            .iter()
+            .map(|&part| Outcome {
+                day,
+                part,
+                expected: None,
+                actual: None,
+                status: Status::Fail,
+                note: note.clone(),
+            })
+            .collect()
+    };
+    let dir = root.join(format!("day{}", day));
+    let Some(input_path) = find_file(&dir, "input") else {
+        return fail(format!("no input in {}", dir.display()));
+    };
+    let actual = match inputs
+        .read(&input_path)
+        .and_then(|input| days::solve(day, &Part::BOTH, Precedence::LeftToRight, &input[..]))
+    {
+        Ok(solved) => BTreeMap::from_iter(
+            solved
+                .answers
+                .into_iter()
+                .map(|answer| (answer.part, answer.answer)),
+        ),
+        // Keep only the headline and location of multi-line diagnostics, to fit in the table.
+        Err(err) => {
+            return fail(
+                err.to_string()
+                    .lines()
+                    .take(2)
+                    .map(str::trim)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        }
+    };
+    let expected = match find_file(&dir, "answers") {
+        Some(path) => inputs
+            .read(&path)
+            .and_then(|contents| Ok(parse_answers(std::str::from_utf8(&contents)?)?))
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err)),
+        None => Err("no recorded answers".to_string()),
+    };
+    Part::BOTH
+        .iter()
+        .map(|&part| {
+            let actual = actual.get(&part).cloned();
+            let (expected, status, note) = match &expected {
+                Err(note) => (None, Status::Fail, note.clone()),
+                Ok(answers) => match answers.get(&part) {
+                    None => (None, Status::Fail, "no recorded answer".to_string()),
+                    Some(e) if Some(e) == actual.as_ref() => {
+                        (Some(e.clone()), Status::Pass, String::new())
+                    }
+                    Some(e) => (Some(e.clone()), Status::Changed, String::new()),
+                },
+            };
+            Outcome {
+                day,
+                part,
+                expected,
+                actual,
+                status,
+                note,
+            }
+        })
+        .collect()
+}
+
+/// Runs each of the given days against its input in `<root>/dayN/`, as [`verify_day`] does.
+pub fn verify_days(
+    inputs: &mut Inputs,
+    root: &Path,
+    days: impl IntoIterator<Item = u8>,
+) -> Vec<Outcome> {
+    days.into_iter()
+        .flat_map(|day| verify_day(inputs, root, day))
+        .collect()
+}
+
+/// Formats the outcomes as a table with one row per day and part.
+pub fn table(outcomes: &[Outcome]) -> String {
+    let header = ["day", "part", "status", "expected", "actual", "note"].map(String::from);
+    let rows: Vec<[String; 6]> = std::iter::once(header)
+        .chain(outcomes.iter().map(|outcome| {
+            [
+                outcome.day.to_string(),
+                outcome.part.to_string(),
+                outcome.status.to_string(),
+                outcome.expected.clone().unwrap_or_else(|| "-".into()),
+                outcome.actual.clone().unwrap_or_else(|| "-".into()),
+                outcome.note.clone(),
+            ]
+        }))
+        .collect();
+    let widths: Vec<usize> = (0..6)
+        .map(|col| rows.iter().map(|row| row[col].len()).max().unwrap_or(0))
+        .collect();
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+            cells.join("  ").trim_end().to_string() + "\n"
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Outcome, Status};
+    use crate::input::Inputs;
+    use aoc_common::Part;
+    use std::path::Path;
+
+    fn testdata() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/verify")
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = super::parse_answers("day 1 part 1: 11\nday 1 part 2: 31\n").unwrap();
+        assert_eq!(answers[&Part::One], "11");
+        assert_eq!(answers[&Part::Two], "31");
+        let answers = super::parse_answers("\nday 7 part 2: 11387").unwrap();
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers[&Part::Two], "11387");
+        assert!(super::parse_answers("day 1 part 3: 11").is_err());
+        assert!(super::parse_answers("11\n31").is_err());
+    }
+
+    #[test]
+    fn test_verify_encrypted_pass() {
+        let mut inputs = Inputs::with_passphrase("correct-horse");
+        let outcomes = super::verify_day(&mut inputs, &testdata(), 1);
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome {
+                    day: 1,
+                    part: Part::One,
+                    expected: Some("11".into()),
+                    actual: Some("11".into()),
+                    status: Status::Pass,
+                    note: String::new(),
+                },
+                Outcome {
+                    day: 1,
+                    part: Part::Two,
+                    expected: Some("31".into()),
+                    actual: Some("31".into()),
+                    status: Status::Pass,
+                    note: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_changed_and_missing() {
+        let mut inputs = Inputs::with_passphrase("correct-horse");
+        let outcomes = super::verify_day(&mut inputs, &testdata(), 2);
+        let statuses: Vec<Status> = outcomes.iter().map(|o| o.status).collect();
+        assert_eq!(statuses, vec![Status::Changed, Status::Fail]);
+        assert_eq!(outcomes[0].expected.as_deref(), Some("3"));
+        assert_eq!(outcomes[0].actual.as_deref(), Some("2"));
+        assert_eq!(outcomes[1].note, "no recorded answer");
+        let outcomes = super::verify_day(&mut inputs, &testdata(), 3);
+        assert!(outcomes.iter().all(|o| o.status == Status::Fail));
+        assert!(outcomes[0].note.starts_with("no input"));
+    }
+
+    #[test]
+    fn test_verify_plaintext() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/verify-plaintext");
+        let outcomes = super::verify_days(&mut Inputs::default(), &root, 1..=3);
+        assert_eq!(
+            super::table(&outcomes),
+            "\
+day  part  status   expected  actual  note
+1    1     pass     11        11
+1    2     pass     31        31
+2    1     changed  3         2
+2    2     pass     4         4
+3    1     fail     -         161     no recorded answers
+3    2     fail     -         48      no recorded answers
+"
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let outcomes = vec![Outcome {
+            day: 2,
+            part: Part::One,
+            expected: Some("3".into()),
+            actual: Some("2".into()),
+            status: Status::Changed,
+            note: String::new(),
+        }];
+        assert_eq!(
+            super::table(&outcomes),
+            "day  part  status   expected  actual  note\n2    1     changed  3         2\n"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Random day 1 inputs.
+
+use aoc_common::Rng;
+
+/// Generates `lines` pairs of five-digit location IDs. About a third of the right column repeats
+/// IDs from the left column, so the similarity score isn't trivially zero.
+pub fn generate(seed: u64, lines: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let left: Vec<i64> = (0..lines).map(|_| rng.range(10000..=99999)).collect();
+    let mut out = String::new();
+    for &l in &left {
+        let r = if rng.chance(1.0 / 3.0) {
+            *rng.choose(&left)
+        } else {
+            rng.range(10000..=99999)
+        };
+        out += &format!("{}   {}\n", l, r);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day1;
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_generate() {
+        let input = super::generate(1, 100);
+        assert_eq!(input, super::generate(1, 100));
+        assert_ne!(input, super::generate(2, 100));
+        let parsed = Day1::parse(input.as_bytes()).unwrap();
+        assert_eq!(parsed.0.len(), 100);
+        assert!(Day1::part2(&parsed) > 0);
+    }
+}
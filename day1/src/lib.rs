@@ -0,0 +1,229 @@
+//! Day 1: Historian Hysteria, which compares two lists of location IDs, read as two
+//! whitespace-separated columns.
+
+use aoc_common::parse::numbered_lines;
+use aoc_common::{InputError, Line, ParseError, Solution};
+use std::collections::BTreeMap;
+
+pub mod generator;
+
+/// The [`Solution`] for day 1.
+pub struct Day1;
+
+/// The example input from the puzzle description.
+pub const EXAMPLE_INPUT: &str = "
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+impl Solution for Day1 {
+    type Input = (Vec<isize>, Vec<isize>);
+    type Answer = isize;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Self::Input, InputError> {
+        sorted_cols(r)
+    }
+
+    fn part1((left, right): &Self::Input) -> isize {
+        total_distance(left, right)
+    }
+
+    fn part2((left, right): &Self::Input) -> isize {
+        similarity_score(left, right)
+    }
+}
+
+fn sorted_cols(r: impl std::io::BufRead) -> Result<(Vec<isize>, Vec<isize>), InputError> {
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    for line in numbered_lines(r) {
+        let (number, text) = line?;
+        if text.is_empty() {
+            continue;
+        }
+        let (l, r) = line_to_tuple(Line::new(number, &text))?;
+        left.push(l);
+        right.push(r);
+    }
+    left.sort_unstable();
+    right.sort_unstable();
+    Ok((left, right))
+}
+
+fn line_to_tuple(line: Line) -> Result<(isize, isize), ParseError> {
+    let mut fields = line.fields();
+    let left = line
+        .next_token(&mut fields, "a number")?
+        .parse("a number")?;
+    let right = line
+        .next_token(&mut fields, "a number")?
+        .parse("a number")?;
+    line.expect_end(&mut fields)?;
+    Ok((left, right))
+}
+
+/// Returns the sum of the distances between the left and right lists, pairing them up smallest
+/// to largest. Both lists must already be sorted.
+pub fn total_distance(left: &[isize], right: &[isize]) -> isize {
+    left.iter()
+        .zip(right.iter())
+        .map(|(x, y)| (x - y).abs())
+        .sum()
+}
+
+/// Returns the sum of each number in the left list multiplied by how often it appears in the
+/// right list.
+pub fn similarity_score(left: &[isize], right: &[isize]) -> isize {
+    let r_counts = counts(right.iter().cloned());
+    left.iter().fold(0, |acc, x| {
+        if let Some(y) = r_counts.get(x) {
+            acc + (x * y)
+        } else {
+            acc
+        }
+    })
+}
+
+fn counts(it: impl Iterator<Item = isize>) -> BTreeMap<isize, isize> {
+    let mut m = BTreeMap::new();
+    it.for_each(|x| {
+        m.entry(x).and_modify(|curr| *curr += 1).or_insert(1);
+    });
+    m
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::property::Property;
+    use aoc_common::Solution;
+
+    use super::EXAMPLE_INPUT;
+
+    #[test]
+    fn test_sorted_cols() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let (left, right) = super::sorted_cols(test_input).unwrap();
+        assert_eq!(left, vec![1, 2, 3, 3, 3, 4]);
+        assert_eq!(right, vec![3, 3, 3, 4, 5, 9]);
+    }
+
+    fn line_to_tuple(line: &str) -> (isize, isize) {
+        super::line_to_tuple(aoc_common::Line::new(1, line)).unwrap()
+    }
+
+    #[test]
+    fn test_line_to_tuple() {
+        assert_eq!(line_to_tuple("3   4"), (3, 4));
+        assert_eq!(line_to_tuple("4   3"), (4, 3));
+        assert_eq!(line_to_tuple("2   5"), (2, 5));
+        assert_eq!(line_to_tuple("1   3"), (1, 3));
+        assert_eq!(line_to_tuple("3   9"), (3, 9));
+        assert_eq!(line_to_tuple("3   3"), (3, 3));
+    }
+
+    #[test]
+    fn test_line_to_tuple_errors() {
+        let err = super::line_to_tuple(aoc_common::Line::new(4, "3   x3")).unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (4, 5, "x3"));
+        assert_eq!(err.expected, "a number");
+        let err = super::line_to_tuple(aoc_common::Line::new(2, "3")).unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (2, ""));
+        let err = super::line_to_tuple(aoc_common::Line::new(2, "3 4 5")).unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (5, "5"));
+        assert_eq!(err.expected, "end of line");
+    }
+
+    #[test]
+    fn test_sorted_cols_error_line() {
+        let test_input = std::io::BufReader::new("\n3   4\n4   -\n".as_bytes());
+        let Err(aoc_common::InputError::Parse(err)) = super::sorted_cols(test_input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((err.line, err.column), (3, 5));
+    }
+
+    #[test]
+    fn test_example_distance() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let (left, right) = super::sorted_cols(test_input).unwrap();
+        let result = super::total_distance(&left, &right);
+        let expected = 11; // given expected answer
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_counts() {
+        let input_1: Vec<isize> = vec![3, 4, 2, 1, 3, 3];
+        let result_1 = super::counts(input_1.iter().cloned());
+        assert_eq!(result_1.len(), 4);
+        assert_eq!(result_1[&1], 1);
+        assert_eq!(result_1[&2], 1);
+        assert_eq!(result_1[&3], 3);
+        assert_eq!(result_1[&4], 1);
+        let input_2 = [4, 3, 5, 3, 9, 3];
+        let result_2 = super::counts(input_2.iter().cloned());
+        assert_eq!(result_2.len(), 4);
+        assert_eq!(result_2[&3], 3);
+        assert_eq!(result_2[&4], 1);
+        assert_eq!(result_2[&5], 1);
+        assert_eq!(result_2[&9], 1);
+    }
+
+    #[test]
+    fn test_example_similarity() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let (left, right) = super::sorted_cols(test_input).unwrap();
+        let result = super::similarity_score(&left, &right);
+        let expected = 31; // given expected answer
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn test_total_distance_is_symmetric() {
+        let symmetric: Property<(Vec<isize>, Vec<isize>)> = Property {
+            name: "total_distance is symmetric",
+            holds: |(left, right)| {
+                super::total_distance(left, right) == super::total_distance(right, left)
+            },
+        };
+        symmetric.check(500, |rng| {
+            let len = rng.below(20);
+            let mut column = || -> Vec<isize> {
+                let mut nums: Vec<isize> =
+                    (0..len).map(|_| rng.range(-999..=999) as isize).collect();
+                nums.sort_unstable();
+                nums
+            };
+            (column(), column())
+        });
+    }
+
+    #[test]
+    fn test_parse_sorts_columns() {
+        let sorted: Property<Vec<(isize, isize)>> = Property {
+            name: "parsing yields each column sorted",
+            holds: |pairs| {
+                let text: String = pairs
+                    .iter()
+                    .map(|(l, r)| format!("{}   {}\n", l, r))
+                    .collect();
+                let (mut left, mut right): (Vec<isize>, Vec<isize>) = pairs.iter().copied().unzip();
+                left.sort_unstable();
+                right.sort_unstable();
+                crate::Day1::parse(text.as_bytes()).unwrap() == (left, right)
+            },
+        };
+        sorted.check(500, |rng| {
+            (0..rng.below(20))
+                .map(|_| {
+                    (
+                        rng.range(-99_999..=99_999) as isize,
+                        rng.range(-99_999..=99_999) as isize,
+                    )
+                })
+                .collect()
+        });
+    }
+}
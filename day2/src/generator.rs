@@ -0,0 +1,50 @@
+//! Random day 2 inputs.
+
+use aoc_common::Rng;
+
+/// Generates `lines` reports, one per line.
+pub fn generate(seed: u64, lines: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..lines {
+        let levels: Vec<String> = report(&mut rng).iter().map(isize::to_string).collect();
+        out += &levels.join(" ");
+        out.push('\n');
+    }
+    out
+}
+
+/// Generates a report of five to eight levels. It starts out safe, then has a chance of one or
+/// two levels being replaced, so there is a mix of safe reports, reports that the dampener makes
+/// safe, and unsafe reports.
+pub fn report(rng: &mut Rng) -> Vec<isize> {
+    let len = rng.range(5..=8) as usize;
+    let sign = *rng.choose(&[-1, 1]);
+    let mut level = rng.range(30..=70) as isize;
+    let mut report = vec![level];
+    for _ in 1..len {
+        level += sign * rng.range(1..=3) as isize;
+        report.push(level);
+    }
+    for _ in 0..rng.range(0..=2) {
+        let i = rng.below(len as u64) as usize;
+        report[i] = rng.range(1..=99) as isize;
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day2;
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_generate() {
+        let input = super::generate(1, 200);
+        assert_eq!(input, super::generate(1, 200));
+        let reports = Day2::parse(input.as_bytes()).unwrap();
+        assert_eq!(reports.len(), 200);
+        let (safe, dampened) = (Day2::part1(&reports), Day2::part2(&reports));
+        assert!(0 < safe && safe < dampened && dampened < 200);
+    }
+}
@@ -0,0 +1,302 @@
+//! Day 2: Red-Nosed Reports, which checks which reactor reports, one line of levels each, are safe.
+
+use aoc_common::differential::Differential;
+use aoc_common::parse::numbered_lines;
+use aoc_common::{InputError, Line, Solution};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub mod generator;
+
+/// The [`Solution`] for day 2.
+pub struct Day2;
+
+/// The example input from the puzzle description.
+pub const EXAMPLE_INPUT: &str = "
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<isize>>;
+    type Answer = usize;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Self::Input, InputError> {
+        reports(r).collect()
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        each_report(input).filter(|report| safe(report)).count()
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        each_report(input)
+            .filter(|report| dampener_safe(report))
+            .count()
+    }
+}
+
+/// Iterates over the reports, spread across all cores with the `parallel` feature.
+#[cfg(feature = "parallel")]
+fn each_report(reports: &[Vec<isize>]) -> impl ParallelIterator<Item = &Vec<isize>> {
+    reports.par_iter()
+}
+
+/// Iterates over the reports on the current thread, without the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+fn each_report(reports: &[Vec<isize>]) -> impl Iterator<Item = &Vec<isize>> {
+    reports.iter()
+}
+
+fn reports(r: impl std::io::BufRead) -> impl Iterator<Item = Result<Vec<isize>, InputError>> {
+    numbered_lines(r)
+        .filter(|line| !matches!(line, Ok((_, text)) if text.is_empty()))
+        .map(|line| {
+            let (number, text) = line?;
+            let report = Line::new(number, &text)
+                .fields()
+                .map(|field| field.parse("a number"))
+                .collect::<Result<_, _>>()?;
+            Ok(report)
+        })
+}
+
+/// Returns true if the report is "safe". A report is "safe" if the numbers are either all
+/// increasing or all decreasing, and any two adjacent numbers differ by at least one and at most
+/// three.
+pub fn safe(report: &[isize]) -> bool {
+    let mut pairs = report.windows(2);
+    let Some(first) = pairs.next() else {
+        return true;
+    };
+    let range = match first[1] - first[0] {
+        1..=3 => 1..=3,
+        -3..=-1 => -3..=-1,
+        _ => return false,
+    };
+    for pair in pairs {
+        if !pair_safe(pair, &range) {
+            return false;
+        }
+    }
+    true
+}
+
+fn pair_safe(pair: &[isize], range: &std::ops::RangeInclusive<isize>) -> bool {
+    range.contains(&(pair[1] - pair[0]))
+}
+
+/// Returns true if the report is "safe" after at most one entry has been removed from the report.
+pub fn dampener_safe(report: &[isize]) -> bool {
+    // For simplicity (since removal of the first or second level could change the (in|de)creasing
+    // direction), simply run through the report twice, once checking increasing, once decreasing.
+    dampener_safe_for_range(report, 1..=3) || dampener_safe_for_range(report, -3..=-1)
+}
+
+fn dampener_safe_for_range(report: &[isize], range: std::ops::RangeInclusive<isize>) -> bool {
+    let mut already_removed = false;
+    let mut pairs = report.windows(2).peekable();
+    let mut can_skip_current = true;
+    while let Some(pair) = pairs.next() {
+        let safe = pair_safe(pair, &range);
+        if !safe {
+            if already_removed {
+                return false;
+            }
+            already_removed = true;
+        }
+        // See if we're safe if we skip the next item
+        let Some(next_pair) = pairs.peek() else {
+            // If we skip the next item, we're done, so report is safe.
+            return true;
+        };
+        let can_skip_next = pair_safe(&[pair[0], next_pair[1]], &range);
+        match (safe, can_skip_next, can_skip_current) {
+            (true, _, _) => can_skip_current = can_skip_next,
+            (false, true, _) => {
+                // skip next (the new current), using up our one skip
+                _ = pairs.next();
+                can_skip_current = false; // use skip next (the new current), using it up
+            }
+            (false, false, true) => can_skip_current = false, // can't skip anymore anyway
+            (false, false, false) => return false,
+        }
+    }
+    true
+}
+
+/// `dampener_safe`, checked against `brute_force_dampener_safe`.
+pub const DAMPENER_SAFE: Differential<Vec<isize>, bool> = Differential {
+    name: "dampener_safe",
+    fast: |report| dampener_safe(report),
+    reference: |report| brute_force_dampener_safe(report),
+};
+
+/// Returns the same as `dampener_safe`, by trying the report with each entry removed in turn.
+pub fn brute_force_dampener_safe(report: &[isize]) -> bool {
+    for i in 0..report.len() {
+        let (left, right) = report.split_at(i);
+        let mut joined = Vec::from(left);
+        joined.extend_from_slice(&right[1..]);
+        if safe(&joined) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EXAMPLE_INPUT;
+    use aoc_common::differential::Shrink;
+    use aoc_common::property::Property;
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_reports() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let result: Vec<Vec<isize>> = super::reports(test_input)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let expected: Vec<Vec<isize>> = vec![
+            vec![7, 6, 4, 2, 1],
+            vec![1, 2, 7, 8, 9],
+            vec![9, 7, 6, 2, 1],
+            vec![1, 3, 2, 4, 5],
+            vec![8, 6, 4, 4, 1],
+            vec![1, 3, 6, 7, 9],
+        ];
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_reports_error() {
+        let test_input =
+            std::io::BufReader::new("7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2,4 5".as_bytes());
+        let Some(Err(aoc_common::InputError::Parse(err))) = super::reports(test_input).nth(3)
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((err.line, err.column, err.token.as_str()), (4, 5, "2,4"));
+    }
+
+    #[test]
+    fn test_safe() {
+        test_both_directions(super::safe, vec![7, 6, 4, 2, 1], true);
+        test_both_directions(super::safe, vec![1, 2, 7, 8, 9], false);
+        test_both_directions(super::safe, vec![9, 7, 6, 2, 1], false);
+        test_both_directions(super::safe, vec![1, 3, 2, 4, 5], false);
+        test_both_directions(super::safe, vec![8, 6, 4, 4, 1], false);
+        test_both_directions(super::safe, vec![1, 3, 6, 7, 9], true);
+        test_both_directions(super::safe, vec![4, 3, 6, 7, 9], false);
+        test_both_directions(super::safe, vec![1, 3, 6, 7, 6], false);
+        test_both_directions(super::safe, vec![1], true);
+    }
+
+    fn test_both_directions(
+        safe_fn: impl Fn(&[isize]) -> bool,
+        mut report: Vec<isize>,
+        safe: bool,
+    ) {
+        assert_eq!(safe_fn(&report), safe);
+        report.reverse();
+        assert_eq!(safe_fn(&report), safe);
+    }
+
+    #[test]
+    fn test_dampener_safe() {
+        test_both_directions(super::dampener_safe, vec![7, 6, 4, 2, 1], true);
+        test_both_directions(super::dampener_safe, vec![1, 2, 7, 8, 9], false);
+        test_both_directions(super::dampener_safe, vec![9, 7, 6, 2, 1], false);
+        test_both_directions(super::dampener_safe, vec![1, 3, 2, 4, 5], true);
+        test_both_directions(super::dampener_safe, vec![8, 6, 4, 4, 1], true);
+        test_both_directions(super::dampener_safe, vec![1, 3, 6, 7, 9], true);
+        test_both_directions(super::dampener_safe, vec![4, 3, 6, 7, 9], true);
+        test_both_directions(super::dampener_safe, vec![1, 3, 6, 7, 6], true);
+        test_both_directions(super::dampener_safe, vec![1], true);
+    }
+
+    #[test]
+    fn test_dampener_safe_tough_cases() {
+        assert_eq!(super::dampener_safe(&vec![82, 83, 84, 81, 86]), true);
+        assert_eq!(
+            super::dampener_safe(&vec![76, 74, 71, 69, 67, 68, 64]),
+            true
+        );
+        assert_eq!(super::dampener_safe(&vec![79, 80, 83, 81, 82]), true);
+        assert_eq!(super::dampener_safe(&vec![28, 30, 33, 36, 42, 39]), true);
+        assert_eq!(super::dampener_safe(&vec![85, 83, 80, 82, 78]), true);
+        assert_eq!(super::dampener_safe(&vec![16, 13, 11, 8, 9, 8]), true);
+        assert_eq!(super::dampener_safe(&vec![63, 60, 66, 69, 72, 73]), true);
+    }
+
+    #[test]
+    fn test_count_safe_reports() {
+        let reports = crate::Day2::parse(EXAMPLE_INPUT.as_bytes()).unwrap();
+        assert_eq!(crate::Day2::part1(&reports), 2);
+        assert_eq!(crate::Day2::part2(&reports), 4);
+    }
+
+    #[test]
+    fn test_dampener_safe_matches_brute_force() {
+        crate::DAMPENER_SAFE.check(5000, crate::generator::report);
+    }
+
+    #[test]
+    fn test_safety_ignores_direction() {
+        let reversible: Property<Vec<isize>> = Property {
+            name: "safe and dampener_safe are unchanged by reversing the report",
+            holds: |report| {
+                let reversed: Vec<isize> = report.iter().rev().copied().collect();
+                super::safe(report) == super::safe(&reversed)
+                    && super::dampener_safe(report) == super::dampener_safe(&reversed)
+            },
+        };
+        reversible.check(5000, crate::generator::report);
+    }
+
+    #[test]
+    fn test_safe_implies_dampener_safe() {
+        let implies: Property<Vec<isize>> = Property {
+            name: "safe reports are dampener_safe",
+            holds: |report| !super::safe(report) || super::dampener_safe(report),
+        };
+        implies.check(5000, crate::generator::report);
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        let round_trip: Property<Vec<Vec<isize>>> = Property {
+            name: "parsing printed reports gives them back",
+            holds: |reports| {
+                let text: String = reports
+                    .iter()
+                    .map(|report| {
+                        let levels: Vec<String> = report.iter().map(ToString::to_string).collect();
+                        levels.join(" ") + "\n"
+                    })
+                    .collect();
+                crate::Day2::parse(text.as_bytes()).unwrap() == *reports
+            },
+        };
+        round_trip.check_with(
+            500,
+            |rng| {
+                (0..rng.below(10))
+                    .map(|_| crate::generator::report(rng))
+                    .collect()
+            },
+            |reports| {
+                // Empty reports would print as blank lines, which aren't reports.
+                reports
+                    .shrink()
+                    .into_iter()
+                    .filter(|reports| reports.iter().all(|report| !report.is_empty()))
+                    .collect()
+            },
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Random day 3 inputs.
+
+use aoc_common::Rng;
+
+/// Pieces of corrupted memory: instructions that should be found, and near misses that shouldn't.
+const NOISE: &[&str] = &[
+    "mul",
+    "mul(",
+    "mul[",
+    "do",
+    "don't",
+    "(",
+    ")",
+    ",",
+    "!",
+    "@",
+    "^",
+    "%",
+    "&",
+    "*",
+    "?",
+    " ",
+    "from()",
+    "what()",
+    "select()",
+    "where()",
+    "mul(4*",
+    "mul ( 2 , 4 )",
+    "mul(1234,5)",
+    "do_not",
+    "\n",
+];
+
+/// Generates roughly `bytes` bytes of corrupted memory, a mix of noise, `do()`, `don't()` and
+/// `mul(X,Y)` instructions with one to three digit numbers.
+pub fn generate(seed: u64, bytes: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::with_capacity(bytes + 16);
+    while out.len() < bytes {
+        match rng.below(10) {
+            0..=2 => out += &format!("mul({},{})", rng.range(0..=999), rng.range(0..=999)),
+            3 => out += "do()",
+            4 => out += "don't()",
+            _ => out += *rng.choose(NOISE),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day3;
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_generate() {
+        let input = super::generate(1, 2000);
+        assert_eq!(input, super::generate(1, 2000));
+        assert!(input.len() >= 2000);
+        let memory = Day3::parse(input.as_bytes()).unwrap();
+        let (all, enabled) = (Day3::part1(&memory), Day3::part2(&memory));
+        assert!(0 < enabled && enabled < all);
+    }
+}
@@ -0,0 +1,367 @@
+//! Day 3: Mull It Over, which adds up the `mul(X,Y)` instructions in corrupted memory.
+//!
+//! The memory is scanned as it is read by a [`Scanner`], so inputs of any size can be solved
+//! without holding them in memory. `sum_muls` and `sum_enabled_muls` are the simpler regex-based
+//! reference implementations.
+
+use aoc_common::differential::Differential;
+use aoc_common::input::for_each_chunk;
+use aoc_common::{InputError, Solution};
+use regex::Regex;
+
+pub mod generator;
+
+/// The [`Solution`] for day 3.
+pub struct Day3;
+
+/// The example input from part 2 of the puzzle description, which also works for part 1.
+pub const EXAMPLE_INPUT: &str =
+    "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+impl Solution for Day3 {
+    type Input = Totals;
+    type Answer = usize;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Totals, InputError> {
+        let mut scanner = Scanner::new();
+        for_each_chunk(r, |chunk| scanner.feed(chunk))?;
+        Ok(scanner.totals())
+    }
+
+    fn part1(totals: &Totals) -> usize {
+        totals.all
+    }
+
+    fn part2(totals: &Totals) -> usize {
+        totals.enabled
+    }
+}
+
+/// The sums of the products of the `mul(X,Y)` instructions in some memory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    /// The sum over every instruction, as `sum_muls` returns.
+    pub all: usize,
+    /// The sum over the enabled instructions, as `sum_enabled_muls` returns.
+    pub enabled: usize,
+}
+
+/// The keywords the scanner looks for. `mul(` starts an instruction, and the others enable and
+/// disable the instructions which follow.
+const KEYWORDS: [&[u8]; 3] = [b"mul(", b"do()", b"don't()"];
+
+/// Where the scanner is within a possible keyword or instruction.
+#[derive(Clone, Copy, Debug)]
+enum State {
+    /// The first `len` bytes of `KEYWORDS[keyword]` have been seen. With none seen yet, the
+    /// keyword is irrelevant.
+    Keyword { keyword: usize, len: usize },
+    /// `mul(` has been seen, followed by `digits` digits of `x`.
+    X { x: usize, digits: u8 },
+    /// `mul(X,` has been seen, followed by `digits` digits of `y`.
+    Y { x: usize, y: usize, digits: u8 },
+}
+
+const START: State = State::Keyword { keyword: 0, len: 0 };
+
+/// Adds up `mul(X,Y)` instructions in memory which is fed to it a chunk at a time. Instructions,
+/// `do()` and `don't()` may be split across chunks anywhere.
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    state: State,
+    enabled: bool,
+    totals: Totals,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Scanner::new()
+    }
+}
+
+impl Scanner {
+    pub fn new() -> Self {
+        Scanner {
+            state: START,
+            enabled: true,
+            totals: Totals::default(),
+        }
+    }
+
+    /// Scans the next chunk of memory.
+    pub fn feed(&mut self, chunk: &[u8]) {
+        let mut rest = chunk;
+        while !rest.is_empty() {
+            if let State::Keyword { len: 0, .. } = self.state {
+                // Nothing is in progress, so skip straight to the next byte which could start
+                // a keyword.
+                match rest.iter().position(|&b| b == b'm' || b == b'd') {
+                    Some(i) => rest = &rest[i..],
+                    None => return,
+                }
+            }
+            self.step(rest[0]);
+            rest = &rest[1..];
+        }
+    }
+
+    /// Returns the totals of the instructions seen so far. An instruction which has not been
+    /// finished yet is not counted.
+    pub fn totals(&self) -> Totals {
+        self.totals
+    }
+
+    fn step(&mut self, byte: u8) {
+        self.state = match self.state {
+            State::Keyword { keyword, len } => {
+                let keyword = match (len, byte) {
+                    (0, b'm') => 0,
+                    (0, b'd') => 1,
+                    // `do()` and `don't()` share their first two bytes.
+                    (2, b'n') if keyword == 1 => 2,
+                    (0, _) => return,
+                    _ if KEYWORDS[keyword][len] == byte => keyword,
+                    _ => return self.restart(byte),
+                };
+                match (keyword, len + 1 == KEYWORDS[keyword].len()) {
+                    (0, true) => State::X { x: 0, digits: 0 },
+                    (1, true) => {
+                        self.enabled = true;
+                        START
+                    }
+                    (2, true) => {
+                        self.enabled = false;
+                        START
+                    }
+                    _ => State::Keyword {
+                        keyword,
+                        len: len + 1,
+                    },
+                }
+            }
+            State::X { x, digits } => match byte {
+                b'0'..=b'9' if digits < 3 => State::X {
+                    x: x * 10 + (byte - b'0') as usize,
+                    digits: digits + 1,
+                },
+                b',' if digits > 0 => State::Y { x, y: 0, digits: 0 },
+                _ => return self.restart(byte),
+            },
+            State::Y { x, y, digits } => match byte {
+                b'0'..=b'9' if digits < 3 => State::Y {
+                    x,
+                    y: y * 10 + (byte - b'0') as usize,
+                    digits: digits + 1,
+                },
+                b')' if digits > 0 => {
+                    self.totals.all += x * y;
+                    if self.enabled {
+                        self.totals.enabled += x * y;
+                    }
+                    START
+                }
+                _ => return self.restart(byte),
+            },
+        };
+    }
+
+    /// Abandons whatever was in progress. The byte which broke it might still start a keyword,
+    /// so it is scanned again from the start. No byte before it needs scanning again, as no
+    /// keyword or instruction contains an `m` or `d` after its first byte.
+    fn restart(&mut self, byte: u8) {
+        self.state = START;
+        self.step(byte);
+    }
+}
+
+/// The `Scanner`, fed the memory in chunks of the given size, checked against the regex-based
+/// `sum_muls` and `sum_enabled_muls`.
+pub const SCANNER: Differential<(String, usize), (usize, usize)> = Differential {
+    name: "Scanner",
+    fast: |(memory, chunk_size)| {
+        let mut scanner = Scanner::new();
+        memory
+            .as_bytes()
+            .chunks(*chunk_size)
+            .for_each(|chunk| scanner.feed(chunk));
+        let totals = scanner.totals();
+        (totals.all, totals.enabled)
+    },
+    reference: |(memory, _)| (sum_muls(memory), sum_enabled_muls(memory)),
+};
+
+/// Returns the sum of the products of every well-formed `mul(X,Y)` instruction, where `X` and
+/// `Y` are one to three digit numbers.
+pub fn sum_muls(string: &str) -> usize {
+    let re = Regex::new(r"mul\((?<x>[0-9]([0-9]?)([0-9]?)),(?<y>[0-9]([0-9]?)([0-9]?))\)").unwrap();
+    re.captures_iter(string)
+        .map(|caps| {
+            let x: usize = caps.name("x").unwrap().as_str().parse().unwrap();
+            let y: usize = caps.name("y").unwrap().as_str().parse().unwrap();
+            x * y
+        })
+        .sum()
+}
+
+/// Returns the sum of the products of the enabled `mul(X,Y)` instructions.
+///
+/// The `do()` and `don't()` strings in the input "enable" and "disable" all `mul(X,Y)`
+/// instructions which follow, until the next occurrence of `do()` or `don't()`. Only the most
+/// recent `do()` or `don't()` takes effect. The `mul(X,Y)` instructions are enabled initially.
+pub fn sum_enabled_muls(string: &str) -> usize {
+    string
+        .split("do()")
+        .map(|s| match s.find("don't()") {
+            Some(ind) => &s[..ind],
+            None => s,
+        })
+        .map(sum_muls)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Day3, Scanner, Totals};
+    use aoc_common::property::Property;
+    use aoc_common::Solution;
+
+    use super::EXAMPLE_INPUT;
+
+    #[test]
+    fn test_sum_muls() {
+        let input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        let result = super::sum_muls(input);
+        assert_eq!(result, 161);
+    }
+
+    #[test]
+    fn test_sum_muls_multiline() {
+        let input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))\nxmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        let result = super::sum_muls(input);
+        assert_eq!(result, 322);
+    }
+
+    #[test]
+    fn test_sum_enabled_muls() {
+        let result = super::sum_enabled_muls(EXAMPLE_INPUT);
+        assert_eq!(result, 48);
+    }
+
+    #[test]
+    fn test_parse() {
+        let totals = Day3::parse(EXAMPLE_INPUT.as_bytes()).unwrap();
+        assert_eq!(
+            totals,
+            Totals {
+                all: 161,
+                enabled: 48
+            }
+        );
+    }
+
+    #[test]
+    fn test_scanner_across_chunks() {
+        let mut scanner = Scanner::new();
+        for chunk in [
+            "mu",
+            "l(12",
+            "3,",
+            "4",
+            ")don",
+            "'",
+            "t(",
+            ")mmul(2,2)",
+            "d",
+            "o()mul(1,1)",
+        ] {
+            scanner.feed(chunk.as_bytes());
+        }
+        assert_eq!(
+            scanner.totals(),
+            Totals {
+                all: 123 * 4 + 4 + 1,
+                enabled: 123 * 4 + 1
+            }
+        );
+        scanner.feed(b"mul(5,");
+        assert_eq!(scanner.totals().all, 497);
+    }
+
+    #[test]
+    fn test_scanner_near_misses() {
+        for memory in [
+            "mul(1234,5)",
+            "mul(,5)",
+            "mul(5,)",
+            "mul ( 2 , 4 )",
+            "mul(4*",
+            "mumul[2,3)",
+        ] {
+            let mut scanner = Scanner::new();
+            scanner.feed(memory.as_bytes());
+            assert_eq!(scanner.totals().all, super::sum_muls(memory), "{}", memory);
+        }
+    }
+
+    #[test]
+    fn test_scanner_matches_regex() {
+        crate::SCANNER.check_with(
+            500,
+            |rng| {
+                let bytes = rng.below(200) as usize;
+                let memory = crate::generator::generate(rng.next_u64(), bytes);
+                (memory, rng.range(1..=16) as usize)
+            },
+            |(memory, chunk_size)| {
+                let mut shrinks: Vec<(String, usize)> = (0..memory.len())
+                    .map(|i| {
+                        let mut removed = memory.clone();
+                        removed.remove(i);
+                        (removed, *chunk_size)
+                    })
+                    .collect();
+                if *chunk_size > 1 {
+                    shrinks.push((memory.clone(), chunk_size - 1));
+                }
+                shrinks
+            },
+        );
+    }
+
+    fn scan(memory: &str) -> Totals {
+        Day3::parse(memory.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_enabled_within_all() {
+        let within: Property<String> = Property {
+            name: "the enabled total is at most the total",
+            holds: |memory| {
+                let totals = scan(memory);
+                totals.enabled <= totals.all
+            },
+        };
+        within.check(500, |rng| {
+            let bytes = rng.below(300) as usize;
+            crate::generator::generate(rng.next_u64(), bytes)
+        });
+    }
+
+    #[test]
+    fn test_totals_add_across_lines() {
+        let additive: Property<(String, String)> = Property {
+            name: "the total of two lines is the sum of their totals",
+            holds: |(first, second)| {
+                let joined = scan(&format!("{}\n{}", first, second)).all;
+                joined == scan(first).all + scan(second).all
+            },
+        };
+        additive.check(500, |rng| {
+            let mut memory = || {
+                let bytes = rng.below(100) as usize;
+                crate::generator::generate(rng.next_u64(), bytes)
+            };
+            (memory(), memory())
+        });
+    }
+}
@@ -0,0 +1,29 @@
+//! Random day 4 inputs.
+
+use aoc_common::Rng;
+
+/// Generates a `size` by `size` word search made only of the letters `X`, `M`, `A` and `S`.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::with_capacity((size + 1) * size);
+    for _ in 0..size {
+        out.extend((0..size).map(|_| *rng.choose(&['X', 'M', 'A', 'S'])));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day4;
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_generate() {
+        let input = super::generate(1, 30);
+        assert_eq!(input, super::generate(1, 30));
+        let grid = Day4::parse(input.as_bytes()).unwrap();
+        assert_eq!((grid.width(), grid.height()), (30, 30));
+        assert!(Day4::part1(&grid) > 0 && Day4::part2(&grid) > 0);
+    }
+}
@@ -0,0 +1,300 @@
+//! Day 4: Ceres Search, which counts `XMAS` and crossed `MAS` in a word search.
+
+use aoc_common::render::Picture;
+use aoc_common::{Cells, Direction, Grid, InputError, Point, Solution};
+
+pub mod generator;
+
+/// The [`Solution`] for day 4.
+pub struct Day4;
+
+/// The example input from the puzzle description.
+pub const EXAMPLE_INPUT: &str = "
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+impl Solution for Day4 {
+    type Input = Grid;
+    type Answer = usize;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Grid, InputError> {
+        Grid::parse(r)
+    }
+
+    fn part1(grid: &Grid) -> usize {
+        grid.count_occurrences("XMAS")
+    }
+
+    fn part2(grid: &Grid) -> usize {
+        grid.count_x_mas_occurrences()
+    }
+}
+
+/// Word-search queries over a grid of letters, dense or sparse.
+pub trait WordSearch: Cells<Cell = u8> + Sized {
+    /// Returns the position of every occurrence of the letter.
+    fn find_coords_of(&self, first: u8) -> impl Iterator<Item = Point>;
+    /// Returns iterators over the letters in each of the eight directions from `start`, clockwise
+    /// from the right, starting with the letter there.
+    fn iters_from(&self, start: Point) -> Vec<GridIter<'_, Self>>;
+    /// Returns where each occurrence of `string` in a straight line starts, and the direction it
+    /// reads in.
+    fn find_occurrences(&self, string: &str) -> impl Iterator<Item = (Point, Direction)>;
+    /// Returns how many times `string` appears in a straight line in any direction.
+    fn count_occurrences(&self, string: &str) -> usize;
+    /// Returns how many times two `MAS` cross diagonally at their `A`, each written either way.
+    fn count_x_mas_occurrences(&self) -> usize;
+}
+
+impl<G: Cells<Cell = u8>> WordSearch for G {
+    fn find_coords_of(&self, first: u8) -> impl Iterator<Item = Point> {
+        self.cells()
+            .filter(move |&(_, &letter)| letter == first)
+            .map(|(point, _)| point)
+    }
+
+    fn iters_from(&self, start: Point) -> Vec<GridIter<'_, G>> {
+        (0..8)
+            .map(|eighths| GridIter {
+                grid: self,
+                current: start,
+                direction: Direction::Right.rotate(eighths),
+            })
+            .collect()
+    }
+
+    fn find_occurrences(&self, string: &str) -> impl Iterator<Item = (Point, Direction)> {
+        string
+            .bytes()
+            .next()
+            .into_iter()
+            .flat_map(|first| self.find_coords_of(first))
+            .flat_map(|start| self.iters_from(start)) // flatten the vecs of iters
+            .filter_map(|iter| {
+                let (start, direction) = (iter.current, iter.direction);
+                (iter.bytes(string.len()) == string.as_bytes()).then_some((start, direction))
+            })
+    }
+
+    fn count_occurrences(&self, string: &str) -> usize {
+        self.find_occurrences(string).count()
+    }
+
+    fn count_x_mas_occurrences(&self) -> usize {
+        self.find_coords_of(b'A')
+            .map(|centre| {
+                // The two diagonals through the centre, each read from one end to the other.
+                [Direction::DownRight, Direction::UpRight].map(|direction| {
+                    GridIter {
+                        grid: self,
+                        current: centre.step(direction.reverse()),
+                        direction,
+                    }
+                    .bytes(3)
+                })
+            })
+            .filter(|x_bytes| {
+                x_bytes
+                    .iter()
+                    .all(|bytes| bytes == "MAS".as_bytes() || bytes == "SAM".as_bytes())
+            })
+            .count()
+    }
+}
+
+/// Draws the grid with the letters of every `XMAS` highlighted.
+pub fn picture<G: WordSearch>(grid: &G) -> Picture<'_, G> {
+    let letters = grid
+        .find_occurrences("XMAS")
+        .flat_map(|(start, direction)| (0..4).map(move |i| start + direction.delta() * i));
+    Picture::new(grid).highlight(letters)
+}
+
+/// Walks the grid in a straight line, yielding the letter at each cell until it leaves the grid.
+pub struct GridIter<'a, G = Grid> {
+    grid: &'a G,
+    current: Point,
+    direction: Direction,
+}
+
+impl<G: Cells<Cell = u8>> Iterator for GridIter<'_, G> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let char_here = self.grid.get(self.current).copied();
+        self.current = self.current.step(self.direction);
+        char_here
+    }
+}
+
+impl<G: Cells<Cell = u8>> GridIter<'_, G> {
+    fn bytes(self, len: usize) -> Vec<u8> {
+        self.take(len).collect::<Vec<u8>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::WordSearch;
+    use aoc_common::differential::Shrink;
+    use aoc_common::property::Property;
+    use aoc_common::{Direction, Grid, Point, SparseGrid};
+
+    use super::EXAMPLE_INPUT;
+
+    #[test]
+    fn test_find_coords_of() {
+        let grid = EXAMPLE_INPUT.parse::<super::Grid>().unwrap();
+        let coords: Vec<(isize, isize)> = grid
+            .find_coords_of(b'X')
+            .map(|point| (point.row, point.col))
+            .collect();
+        assert_eq!(
+            coords,
+            vec![
+                (0, 4),
+                (0, 5),
+                (1, 4),
+                (2, 2),
+                (2, 4),
+                (3, 9),
+                (4, 0),
+                (4, 6),
+                (5, 0),
+                (5, 1),
+                (5, 5),
+                (5, 6),
+                (6, 7),
+                (7, 2),
+                (8, 5),
+                (9, 1),
+                (9, 3),
+                (9, 5),
+                (9, 9),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_iters_from() {
+        let grid = EXAMPLE_INPUT.parse::<super::Grid>().unwrap();
+        let strs: Vec<String> = grid
+            .iters_from(aoc_common::Point::new(1, 2))
+            .into_iter()
+            .map(|iter| String::from_utf8(iter.bytes(4)).unwrap())
+            .collect();
+        assert_eq!(
+            strs,
+            vec!["AMXM", "ASAM", "AXAA", "AMM", "ASM", "AM", "AM", "AS",],
+        );
+    }
+
+    #[test]
+    fn test_count_occurrences_xmas() {
+        let grid = EXAMPLE_INPUT.parse::<super::Grid>().unwrap();
+        let result = grid.count_occurrences("XMAS");
+        assert_eq!(result, 18);
+    }
+
+    #[test]
+    fn test_count_x_mas_occurrences() {
+        let grid = EXAMPLE_INPUT.parse::<super::Grid>().unwrap();
+        let result = grid.count_x_mas_occurrences();
+        assert_eq!(result, 9);
+    }
+
+    #[test]
+    fn test_find_occurrences() {
+        let grid = "XMAS\n.AM.\nSAMX\n".parse::<super::Grid>().unwrap();
+        let found: Vec<(Point, Direction)> = grid.find_occurrences("XMAS").collect();
+        assert_eq!(
+            found,
+            vec![
+                (Point::new(0, 0), Direction::Right),
+                (Point::new(2, 3), Direction::Left),
+            ]
+        );
+        assert_eq!(grid.find_occurrences("").count(), 0);
+    }
+
+    #[test]
+    fn test_picture() {
+        let grid = "XMAS\n.A..\n".parse::<super::Grid>().unwrap();
+        let yellow = |c| format!("\x1b[30;43m{}\x1b[0m", c);
+        assert_eq!(
+            super::picture(&grid).ansi(),
+            format!(
+                "{}{}{}{}\n.A..\n",
+                yellow('X'),
+                yellow('M'),
+                yellow('A'),
+                yellow('S')
+            )
+        );
+    }
+
+    #[test]
+    fn test_sparse() {
+        let grid = EXAMPLE_INPUT.parse::<super::Grid>().unwrap();
+        let sparse = SparseGrid::from_grid(&grid, b'.');
+        assert_eq!(sparse.count_occurrences("XMAS"), 18);
+        assert_eq!(sparse.count_x_mas_occurrences(), 9);
+        // Two words a long way apart on an unbounded map, one of them written upwards.
+        let mut sparse = SparseGrid::new(None);
+        for (i, letter) in "XMAS".bytes().enumerate() {
+            sparse.insert(Point::new(-1_000_000_000, i as isize), letter);
+            sparse.insert(Point::new(1_000_000_000 - i as isize, 7), letter);
+        }
+        assert_eq!(sparse.count_occurrences("XMAS"), 2);
+    }
+
+    /// Returns both counts for the word search generated from `seed` and `size`, the grid
+    /// transposed, and the grid mirrored left to right.
+    fn counts_under_symmetry((seed, size): &(u64, usize)) -> [(usize, usize); 3] {
+        let grid: Grid = crate::generator::generate(*seed, *size).parse().unwrap();
+        let rows: Vec<Vec<u8>> = grid.rows().map(<[u8]>::to_vec).collect();
+        let transposed = (0..grid.width())
+            .map(|col| rows.iter().map(|row| row[col]).collect())
+            .collect();
+        let mirrored = rows
+            .iter()
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+        [grid, Grid::from_rows(transposed), Grid::from_rows(mirrored)].map(|grid| {
+            (
+                grid.count_occurrences("XMAS"),
+                grid.count_x_mas_occurrences(),
+            )
+        })
+    }
+
+    #[test]
+    fn test_counts_are_symmetric() {
+        let symmetric: Property<(u64, usize)> = Property {
+            name: "counts are unchanged by transposing or mirroring the grid",
+            holds: |input| {
+                let [counts, transposed, mirrored] = counts_under_symmetry(input);
+                counts == transposed && counts == mirrored
+            },
+        };
+        symmetric.check_with(
+            200,
+            |rng| (rng.next_u64(), rng.range(1..=20) as usize),
+            |input| {
+                input
+                    .shrink()
+                    .into_iter()
+                    .filter(|&(_, size)| size > 0)
+                    .collect()
+            },
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Random day 5 inputs.
+
+use aoc_common::Rng;
+
+/// How many distinct page numbers there are; every pair of them has a rule.
+const PAGES: usize = 49;
+
+/// Generates a rule for every pair of 49 two-digit pages, following one random order of the
+/// pages, and `updates` updates of an odd number of pages. About half the updates are already
+/// in order.
+pub fn generate(seed: u64, updates: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut order: Vec<i64> = (10..=99).collect();
+    rng.shuffle(&mut order);
+    order.truncate(PAGES);
+    let mut rules: Vec<(i64, i64)> = (0..PAGES)
+        .flat_map(|i| (i + 1..PAGES).map(move |j| (i, j)))
+        .map(|(i, j)| (order[i], order[j]))
+        .collect();
+    rng.shuffle(&mut rules);
+    let mut out = String::new();
+    for (x, y) in rules {
+        out += &format!("{}|{}\n", x, y);
+    }
+    out.push('\n');
+    for _ in 0..updates {
+        let len = 2 * rng.range(2..=11) as usize + 1;
+        let mut positions: Vec<usize> = (0..PAGES).collect();
+        rng.shuffle(&mut positions);
+        positions.truncate(len);
+        if rng.chance(0.5) {
+            positions.sort_unstable();
+        }
+        let update: Vec<String> = positions.iter().map(|&i| order[i].to_string()).collect();
+        out += &update.join(",");
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day5;
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_generate() {
+        let input = super::generate(1, 50);
+        assert_eq!(input, super::generate(1, 50));
+        let (rules, updates) = Day5::parse(input.as_bytes()).unwrap();
+        assert_eq!(
+            rules.values().map(|ys| ys.len()).sum::<usize>(),
+            49 * 48 / 2
+        );
+        assert_eq!(updates.len(), 50);
+        assert!(updates.iter().all(|update| update.len() % 2 == 1));
+        assert!(Day5::part1(&(rules.clone(), updates.clone())) > 0);
+        assert!(Day5::part2(&(rules, updates)) > 0);
+    }
+}
@@ -0,0 +1,439 @@
+//! Day 5: Print Queue, which checks and fixes the page order of updates against ordering rules.
+
+use aoc_common::parse::numbered_lines;
+use aoc_common::{InputError, Line, ParseError, Solution};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub mod generator;
+
+/// The [`Solution`] for day 5.
+pub struct Day5;
+
+/// The example input from the puzzle description.
+pub const EXAMPLE_INPUT: &str = "
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+impl Solution for Day5 {
+    /// The ordering rules as produced by `rules_to_map`, and the updates.
+    type Input = (BTreeMap<usize, BTreeSet<usize>>, Vec<Vec<usize>>);
+    type Answer = usize;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Self::Input, InputError> {
+        let (rules, updates) = get_rules_and_updates(r)?;
+        Ok((rules_to_map(rules), updates))
+    }
+
+    fn part1((rule_map, updates): &Self::Input) -> usize {
+        let valid = updates
+            .iter()
+            .filter(|upd| update_is_valid(upd, rule_map))
+            .cloned();
+        sum_of_middles(valid)
+    }
+
+    fn part2((rule_map, updates): &Self::Input) -> usize {
+        let fixed = updates
+            .iter()
+            .filter(|upd| !update_is_valid(upd, rule_map))
+            .map(|inv| correct_order(inv.clone(), rule_map));
+        sum_of_middles(fixed)
+    }
+}
+
+// Rules are X|Y where X must come before Y (if X and Y are both present) and updates are a map
+// from number to position in the list of numbers.
+type RulesAndUpdates = (Vec<(usize, usize)>, Vec<Vec<usize>>);
+
+fn get_rules_and_updates(r: impl std::io::BufRead) -> Result<RulesAndUpdates, InputError> {
+    // The rules and updates are separated by a blank line, and blank lines before either section
+    // are skipped. Anything after the updates is ignored.
+    enum Section {
+        BeforeRules,
+        Rules,
+        BeforeUpdates,
+        Updates,
+    }
+    let (mut rules, mut updates) = (Vec::new(), Vec::new());
+    let mut section = Section::BeforeRules;
+    for line in numbered_lines(r) {
+        let (number, text) = line?;
+        let line = Line::new(number, &text);
+        section = match (section, text.is_empty()) {
+            (Section::BeforeRules, true) => Section::BeforeRules,
+            (Section::BeforeRules | Section::Rules, false) => {
+                rules.push(parse_rule(line)?);
+                Section::Rules
+            }
+            (Section::Rules | Section::BeforeUpdates, true) => Section::BeforeUpdates,
+            (Section::BeforeUpdates | Section::Updates, false) => {
+                updates.push(parse_update(line)?);
+                Section::Updates
+            }
+            (Section::Updates, true) => break,
+        }
+    }
+    Ok((rules, updates))
+}
+
+fn parse_rule(line: Line) -> Result<(usize, usize), ParseError> {
+    let (x, y) = line.split_once('|')?;
+    Ok((x.parse("a page number")?, y.parse("a page number")?))
+}
+
+fn parse_update(line: Line) -> Result<Vec<usize>, ParseError> {
+    line.split(',')
+        .map(|page| page.parse("a page number"))
+        .collect()
+}
+
+/// Convert from X|Y to a map from X: {Y, ...} where all Y in the value may not occur before X in
+/// an update.
+pub fn rules_to_map(rules: Vec<(usize, usize)>) -> BTreeMap<usize, BTreeSet<usize>> {
+    let mut map: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    for (x, y) in rules {
+        map.entry(x).or_default().insert(y);
+    }
+    map
+}
+
+/// Returns whether the update obeys every rule whose pages both appear in it.
+pub fn update_is_valid(update: &[usize], rules: &BTreeMap<usize, BTreeSet<usize>>) -> bool {
+    let update_map = update_to_map(update);
+    for (x, ys) in rules {
+        for y in ys {
+            let (Some(x_ind), Some(y_ind)) = (update_map.get(x), update_map.get(y)) else {
+                continue;
+            };
+            if x_ind >= y_ind {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+fn update_to_map(update: &[usize]) -> BTreeMap<usize, usize> {
+    BTreeMap::from_iter(update.iter().enumerate().map(|(i, x)| (*x, i)))
+}
+
+fn sum_of_middles(updates: impl Iterator<Item = Vec<usize>>) -> usize {
+    updates.map(|upd| upd[upd.len() / 2]).sum()
+}
+
+/// Reorders an update so that it obeys the rules.
+pub fn correct_order(update: Vec<usize>, rules: &BTreeMap<usize, BTreeSet<usize>>) -> Vec<usize> {
+    let update_len = update.len();
+    update
+        .into_iter()
+        .fold(Vec::with_capacity(update_len), |mut acc, num| {
+            let Some(must_precede) = rules.get(&num) else {
+                acc.push(num);
+                return acc;
+            };
+            match acc.iter().position(|x| must_precede.contains(x)) {
+                Some(pos) => acc.insert(pos, num),
+                None => acc.push(num),
+            }
+            acc
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::differential::Shrink;
+    use aoc_common::property::Property;
+    use aoc_common::{InputError, Rng, Solution};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::EXAMPLE_INPUT;
+
+    #[test]
+    fn test_get_rules_and_updates() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let (rules, updates) = super::get_rules_and_updates(test_input).unwrap();
+        assert_eq!(
+            rules,
+            [
+                (47, 53),
+                (97, 13),
+                (97, 61),
+                (97, 47),
+                (75, 29),
+                (61, 13),
+                (75, 53),
+                (29, 13),
+                (97, 29),
+                (53, 29),
+                (61, 53),
+                (97, 53),
+                (61, 29),
+                (47, 13),
+                (75, 47),
+                (97, 75),
+                (47, 61),
+                (75, 61),
+                (47, 29),
+                (75, 13),
+                (53, 13),
+            ]
+        );
+        assert_eq!(
+            updates,
+            vec![
+                vec![75, 47, 61, 53, 29],
+                vec![97, 61, 53, 29, 13],
+                vec![75, 29, 13],
+                vec![75, 97, 47, 61, 53],
+                vec![61, 13, 29],
+                vec![97, 13, 75, 29, 47],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_rules_and_updates_errors() {
+        let test_input = std::io::BufReader::new("\n47|53\n97 13\n".as_bytes());
+        let Err(InputError::Parse(err)) = crate::get_rules_and_updates(test_input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((err.line, err.column, err.token.as_str()), (3, 6, ""));
+        assert_eq!(err.expected, "`|`");
+        let test_input = std::io::BufReader::new("47|53\n\n75,47,x\n".as_bytes());
+        let Err(InputError::Parse(err)) = crate::get_rules_and_updates(test_input) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((err.line, err.column, err.token.as_str()), (3, 7, "x"));
+        assert_eq!(err.expected, "a page number");
+    }
+
+    #[test]
+    fn test_rules_to_map() {
+        let rules = vec![
+            (47, 53),
+            (97, 13),
+            (97, 61),
+            (97, 47),
+            (75, 29),
+            (61, 13),
+            (75, 53),
+            (29, 13),
+            (97, 29),
+            (53, 29),
+            (61, 53),
+            (97, 53),
+            (61, 29),
+            (47, 13),
+            (75, 47),
+            (97, 75),
+            (47, 61),
+            (75, 61),
+            (47, 29),
+            (75, 13),
+            (53, 13),
+        ];
+        let map = crate::rules_to_map(rules);
+        let expected: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::from([
+            (47, BTreeSet::from([53, 13, 61, 29])),
+            (97, BTreeSet::from([13, 61, 47, 29, 53, 75])),
+            (75, BTreeSet::from([29, 53, 47, 61, 13])),
+            (61, BTreeSet::from([13, 53, 29])),
+            (29, BTreeSet::from([13])),
+            (53, BTreeSet::from([29, 13])),
+        ]);
+        assert_eq!(map, expected);
+    }
+
+    #[test]
+    fn test_update_is_valid() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let (rules, updates) = crate::get_rules_and_updates(test_input).unwrap();
+        let rule_map = crate::rules_to_map(rules);
+        let valid_updates: Vec<Vec<usize>> = updates
+            .into_iter()
+            .filter(|upd| crate::update_is_valid(upd, &rule_map))
+            .collect();
+        assert_eq!(
+            valid_updates,
+            vec![
+                vec![75, 47, 61, 53, 29],
+                vec![97, 61, 53, 29, 13],
+                vec![75, 29, 13]
+            ]
+        )
+    }
+
+    #[test]
+    fn test_update_to_map() {
+        assert_eq!(
+            crate::update_to_map(&[75, 47, 61, 53, 29]),
+            BTreeMap::from([(75, 0), (47, 1), (61, 2), (53, 3), (29, 4)])
+        );
+        assert_eq!(
+            crate::update_to_map(&[97, 61, 53, 29, 13]),
+            BTreeMap::from([(97, 0), (61, 1), (53, 2), (29, 3), (13, 4)])
+        );
+        assert_eq!(
+            crate::update_to_map(&[75, 29, 13]),
+            BTreeMap::from([(75, 0), (29, 1), (13, 2)])
+        );
+        assert_eq!(
+            crate::update_to_map(&[75, 97, 47, 61, 53]),
+            BTreeMap::from([(75, 0), (97, 1), (47, 2), (61, 3), (53, 4)])
+        );
+        assert_eq!(
+            crate::update_to_map(&[61, 13, 29]),
+            BTreeMap::from([(61, 0), (13, 1), (29, 2)])
+        );
+        assert_eq!(
+            crate::update_to_map(&[97, 13, 75, 29, 47]),
+            BTreeMap::from([(97, 0), (13, 1), (75, 2), (29, 3), (47, 4)])
+        );
+    }
+
+    #[test]
+    fn test_sum_of_middles() {
+        let valid_updates: Vec<Vec<usize>> = vec![
+            vec![75, 47, 61, 53, 29],
+            vec![97, 61, 53, 29, 13],
+            vec![75, 29, 13],
+        ];
+        let sum = crate::sum_of_middles(valid_updates.into_iter());
+        assert_eq!(sum, 61 + 53 + 29);
+    }
+
+    #[test]
+    fn test_sum_of_valid_update_middle_pages() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let input = crate::Day5::parse(test_input).unwrap();
+        let result = crate::Day5::part1(&input);
+        assert_eq!(result, 143);
+    }
+
+    #[test]
+    fn test_sum_of_fixed_update_middle_pages() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let input = crate::Day5::parse(test_input).unwrap();
+        let result = crate::Day5::part2(&input);
+        assert_eq!(result, 123);
+    }
+
+    /// An order of at least two distinct pages, with a rule for every pair of them, and an update
+    /// of some of those pages in any order.
+    type Ordered = (Vec<usize>, Vec<usize>);
+
+    fn ordered(rng: &mut Rng) -> Ordered {
+        let mut order: Vec<usize> = (10..=99).collect();
+        rng.shuffle(&mut order);
+        order.truncate(rng.range(2..=20) as usize);
+        let mut update = order.clone();
+        rng.shuffle(&mut update);
+        update.truncate(rng.range(0..=update.len() as i64) as usize);
+        (order, update)
+    }
+
+    /// Shrinks the order and the update, keeping at least one rule, the pages of each distinct, and
+    /// the update's pages within the order.
+    fn shrink_ordered(input: &Ordered) -> Vec<Ordered> {
+        let distinct = |pages: &[usize]| pages.iter().collect::<BTreeSet<_>>().len() == pages.len();
+        input
+            .shrink()
+            .into_iter()
+            .filter(|(order, update)| {
+                order.len() > 1
+                    && distinct(order)
+                    && distinct(update)
+                    && update.iter().all(|page| order.contains(page))
+            })
+            .collect()
+    }
+
+    fn rules(order: &[usize]) -> BTreeMap<usize, BTreeSet<usize>> {
+        let rules = (0..order.len())
+            .flat_map(|i| (i + 1..order.len()).map(move |j| (order[i], order[j])))
+            .collect();
+        crate::rules_to_map(rules)
+    }
+
+    fn check_ordered(property: Property<Ordered>) {
+        property.check_with(1000, ordered, shrink_ordered);
+    }
+
+    #[test]
+    fn test_correct_order_is_valid_permutation() {
+        check_ordered(Property {
+            name: "correct_order returns a valid permutation of the update",
+            holds: |(order, update)| {
+                let rules = rules(order);
+                let corrected = crate::correct_order(update.clone(), &rules);
+                let (mut sorted, mut corrected_sorted) = (update.clone(), corrected.clone());
+                sorted.sort_unstable();
+                corrected_sorted.sort_unstable();
+                sorted == corrected_sorted && crate::update_is_valid(&corrected, &rules)
+            },
+        });
+    }
+
+    #[test]
+    fn test_correct_order_keeps_valid_updates() {
+        check_ordered(Property {
+            name: "correct_order leaves a valid update unchanged",
+            holds: |(order, update)| {
+                let rules = rules(order);
+                !crate::update_is_valid(update, &rules)
+                    || crate::correct_order(update.clone(), &rules) == *update
+            },
+        });
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        check_ordered(Property {
+            name: "parsing printed rules and an update gives them back",
+            holds: |(order, update)| {
+                let rules = rules(order);
+                let mut text = String::new();
+                for (x, ys) in &rules {
+                    for y in ys {
+                        text += &format!("{}|{}\n", x, y);
+                    }
+                }
+                let pages: Vec<String> = update.iter().map(ToString::to_string).collect();
+                text += &format!("\n{}\n", pages.join(","));
+                // An empty update prints as a blank line, which ends the input.
+                let updates = if update.is_empty() {
+                    vec![]
+                } else {
+                    vec![update.clone()]
+                };
+                crate::Day5::parse(text.as_bytes()).unwrap() == (rules, updates)
+            },
+        });
+    }
+}
@@ -0,0 +1,109 @@
+//! Random day 6 inputs.
+
+use crate::{Cell, Facing, GridIter, PatrolMap};
+use aoc_common::{Cells, Grid, Point, Rng};
+
+const DENSITY: f64 = 0.05;
+
+/// Generates a `size` by `size` map with about one cell in twenty an obstacle, and a guard facing
+/// up. Maps where the guard never leaves are thrown away, so part 1 always has an answer, and so
+/// are maps where the guard leaves after visiting fewer than `2 * size` positions, so part 2 has
+/// some work to do.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    loop {
+        let map = map(&mut rng, size);
+        if map.count_positions() >= 2 * size {
+            return map.to_string();
+        }
+    }
+}
+
+/// Generates a `size` by `size` map like `generate`, but without a minimum path length.
+pub fn map(rng: &mut Rng, size: usize) -> Grid<Cell> {
+    loop {
+        let mut map = Grid::new(size, size, Cell::Empty);
+        for row in 0..size {
+            for col in 0..size {
+                if rng.chance(DENSITY) {
+                    map[Point::new(row as isize, col as isize)] = Cell::Obstacle;
+                }
+            }
+        }
+        let start = loop {
+            let (row, col) = (rng.below(size as u64), rng.below(size as u64));
+            let point = Point::new(row as isize, col as isize);
+            if map[point] == Cell::Empty {
+                break point;
+            }
+        };
+        map[start] = Cell::Guard(Facing::Up);
+        if guard_leaves(&map) {
+            return map;
+        }
+    }
+}
+
+/// Returns smaller maps than `map` in which the guard still leaves: with a row or column removed,
+/// or an obstacle cleared.
+pub fn shrink_map(map: &Grid<Cell>) -> Vec<Grid<Cell>> {
+    let cells: Vec<Vec<Cell>> = map.rows().map(<[Cell]>::to_vec).collect();
+    let mut shrinks = Vec::new();
+    for row in 0..cells.len() {
+        let mut removed = cells.to_vec();
+        removed.remove(row);
+        shrinks.push(removed);
+    }
+    for col in 0..map.width() {
+        let mut removed = cells.to_vec();
+        removed.iter_mut().for_each(|line| {
+            line.remove(col);
+        });
+        shrinks.push(removed);
+    }
+    let mut shrinks: Vec<Grid<Cell>> = shrinks.into_iter().map(Grid::from_rows).collect();
+    for (point, _) in map.cells().filter(|&(_, &cell)| cell == Cell::Obstacle) {
+        let mut cleared = map.clone();
+        cleared[point] = Cell::Empty;
+        shrinks.push(cleared);
+    }
+    shrinks.into_iter().filter(guard_leaves).collect()
+}
+
+/// Returns whether the map has a guard and they leave it.
+fn guard_leaves(map: &Grid<Cell>) -> bool {
+    map.find_start()
+        .is_some_and(|(start, direction)| !GridIter::new(map, start, direction).has_cycle())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day6;
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_generate() {
+        let input = super::generate(1, 40);
+        assert_eq!(input, super::generate(1, 40));
+        assert_eq!(input.matches('^').count(), 1);
+        let grid = Day6::parse(input.as_bytes()).unwrap();
+        assert_eq!((grid.width(), grid.height()), (40, 40));
+        assert!(Day6::part1(&grid) >= 80);
+        for seed in 0..20 {
+            let grid = Day6::parse(super::generate(seed, 20).as_bytes()).unwrap();
+            assert!(Day6::part1(&grid) >= 40);
+        }
+    }
+
+    #[test]
+    fn test_shrink_map() {
+        let map = "#..\n.^#\n...\n".parse().unwrap();
+        let shrinks: Vec<String> = super::shrink_map(&map)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert!(shrinks.contains(&".^#\n...\n".to_string()));
+        assert!(shrinks.contains(&"...\n.^#\n...\n".to_string()));
+        assert!(!shrinks.contains(&"#..\n...\n".to_string()));
+    }
+}
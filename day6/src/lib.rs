@@ -0,0 +1,481 @@
+//! Day 6: Guard Gallivant, which follows a guard around a map and finds where an obstacle would
+//! trap it in a loop.
+
+use aoc_common::differential::Differential;
+use aoc_common::render::Picture;
+use aoc_common::{Cells, Direction, FromCell, Grid, InputError, Point, Solution, ToCell};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+
+pub mod generator;
+
+/// The [`Solution`] for day 6.
+pub struct Day6;
+
+/// The example input from the puzzle description.
+pub const EXAMPLE_INPUT: &str = "
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+/// One position on the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Obstacle,
+    /// The guard's starting position, and the direction they face.
+    Guard(Facing),
+}
+
+/// One of the four directions the guard can face at the start.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Facing {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl From<Facing> for Direction {
+    fn from(facing: Facing) -> Direction {
+        match facing {
+            Facing::Up => Direction::Up,
+            Facing::Right => Direction::Right,
+            Facing::Down => Direction::Down,
+            Facing::Left => Direction::Left,
+        }
+    }
+}
+
+impl FromCell for Cell {
+    const EXPECTED: &'static str = "`.`, `#` or a guard (`^`, `>`, `v` or `<`)";
+
+    fn from_cell(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Cell::Empty),
+            '#' => Some(Cell::Obstacle),
+            '^' => Some(Cell::Guard(Facing::Up)),
+            '>' => Some(Cell::Guard(Facing::Right)),
+            'v' => Some(Cell::Guard(Facing::Down)),
+            '<' => Some(Cell::Guard(Facing::Left)),
+            _ => None,
+        }
+    }
+}
+
+impl ToCell for Cell {
+    fn to_cell(&self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Obstacle => '#',
+            Cell::Guard(Facing::Up) => '^',
+            Cell::Guard(Facing::Right) => '>',
+            Cell::Guard(Facing::Down) => 'v',
+            Cell::Guard(Facing::Left) => '<',
+        }
+    }
+}
+
+impl Solution for Day6 {
+    type Input = Grid<Cell>;
+    type Answer = usize;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Grid<Cell>, InputError> {
+        Grid::parse(r)
+    }
+
+    fn part1(grid: &Grid<Cell>) -> usize {
+        grid.count_positions()
+    }
+
+    fn part2(grid: &Grid<Cell>) -> usize {
+        grid.count_obstacle_placements()
+    }
+}
+
+/// Guard patrol queries over a map of empty cells and obstacles, dense or sparse.
+///
+/// On an infinite map, the guard leaves once they are more than one step past every cell that
+/// has been set, since beyond that no obstacle can turn them back.
+pub trait PatrolMap: Cells<Cell = Cell> + Sync {
+    /// Returns the position of the guard and the direction they face, or `None` if the map has no
+    /// guard. Without a guard, no positions are visited and no obstacle can trap anyone.
+    fn find_start(&self) -> Option<(Point, Direction)>;
+    /// Returns how many distinct positions the guard visits before leaving the map.
+    fn count_positions(&self) -> usize;
+    /// Returns every position the guard visits before leaving the map.
+    fn get_positions(&self, include_start: bool) -> BTreeSet<Point>;
+    /// Returns how many positions a single new obstacle could be placed at to trap the guard in a
+    /// loop. With the `parallel` feature, the positions are tried across all cores.
+    fn count_obstacle_placements(&self) -> usize;
+}
+
+impl<G: Cells<Cell = Cell> + Sync> PatrolMap for G {
+    fn find_start(&self) -> Option<(Point, Direction)> {
+        self.cells().find_map(|(point, cell)| match cell {
+            Cell::Guard(facing) => Some((point, Direction::from(*facing))),
+            _ => None,
+        })
+    }
+
+    fn count_positions(&self) -> usize {
+        self.get_positions(true).len()
+    }
+
+    fn get_positions(&self, include_start: bool) -> BTreeSet<Point> {
+        let Some((start, direction)) = self.find_start() else {
+            return BTreeSet::new();
+        };
+        let mut positions = GridIter::new(self, start, direction)
+            .map(|(point, _)| point)
+            .collect::<BTreeSet<Point>>();
+        if include_start {
+            positions.insert(start);
+        }
+        positions
+    }
+
+    fn count_obstacle_placements(&self) -> usize {
+        let Some((start, direction)) = self.find_start() else {
+            return 0;
+        };
+        #[cfg(feature = "parallel")]
+        let candidates = self.get_positions(false).into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let candidates = self.get_positions(false).into_iter();
+        candidates
+            .filter(|&obstacle| {
+                GridIter::new_with_obstacle(self, start, direction, obstacle).has_cycle()
+            })
+            .count()
+    }
+}
+
+/// Draws the map with every position the guard visits highlighted, and an arrow where they
+/// start.
+pub fn picture<G: PatrolMap>(map: &G) -> Picture<'_, G> {
+    let picture = Picture::new(map).highlight(map.get_positions(true));
+    match map.find_start() {
+        Some((start, direction)) => picture.arrow(start, direction),
+        None => picture,
+    }
+}
+
+/// `count_obstacle_placements` on a map, checked against `brute_force_obstacle_placements`.
+/// The map is kept as text so that failures print readably.
+pub const OBSTACLE_PLACEMENTS: Differential<String, usize> = Differential {
+    name: "count_obstacle_placements",
+    fast: |map| parse_map(map).count_obstacle_placements(),
+    reference: |map| brute_force_obstacle_placements(&parse_map(map)),
+};
+
+/// Parses a map which is known to be valid, such as a generated one.
+fn parse_map(map: &str) -> Grid<Cell> {
+    map.parse().expect("the map is valid")
+}
+
+/// Returns the same as `count_obstacle_placements`, by trying an obstacle in every open position
+/// rather than only those on the guard's path.
+pub fn brute_force_obstacle_placements(grid: &Grid<Cell>) -> usize {
+    let Some((start, direction)) = grid.find_start() else {
+        return 0;
+    };
+    grid.cells()
+        .filter(|&(_, &cell)| cell == Cell::Empty)
+        .filter(|&(obstacle, _)| {
+            GridIter::new_with_obstacle(grid, start, direction, obstacle).has_cycle()
+        })
+        .count()
+}
+
+struct GridIter<'a, G> {
+    grid: &'a G,
+    current: Point,
+    direction: Direction,
+    obstacle: Option<Point>,
+    /// On an infinite map, the corners of the rectangle the guard leaves by: one step beyond every
+    /// set cell, the start and the new obstacle.
+    limits: Option<(Point, Point)>,
+}
+
+impl<'a, G: Cells<Cell = Cell>> GridIter<'a, G> {
+    fn new(grid: &'a G, start: Point, direction: Direction) -> Self {
+        GridIter {
+            grid,
+            current: start,
+            direction,
+            obstacle: None,
+            limits: limits(grid, [start]),
+        }
+    }
+
+    fn new_with_obstacle(grid: &'a G, start: Point, direction: Direction, obstacle: Point) -> Self {
+        GridIter {
+            grid,
+            current: start,
+            direction,
+            obstacle: Some(obstacle),
+            limits: limits(grid, [start, obstacle]),
+        }
+    }
+
+    fn has_cycle(mut self) -> bool {
+        let mut position_directions: BTreeSet<(Point, Direction)> = BTreeSet::new();
+        for pos_dir in self.by_ref() {
+            if !position_directions.insert(pos_dir) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Returns the corners of the rectangle reaching one step beyond every set cell and the given
+/// points, if the map is infinite. A guard who steps out of it is walking away from every
+/// obstacle, and would walk on forever.
+fn limits<G: Cells>(grid: &G, points: impl IntoIterator<Item = Point>) -> Option<(Point, Point)> {
+    if grid.is_finite() {
+        return None;
+    }
+    let (min, max) = grid.cells().map(|(point, _)| point).chain(points).fold(
+        None,
+        |limits, point| match limits {
+            None => Some((point, point)),
+            Some((min, max)) => Some((
+                Point::new(point.row.min(min.row), point.col.min(min.col)),
+                Point::new(point.row.max(max.row), point.col.max(max.col)),
+            )),
+        },
+    )?;
+    Some((min - Point::new(1, 1), max + Point::new(1, 1)))
+}
+
+impl<G: Cells<Cell = Cell>> Iterator for GridIter<'_, G> {
+    type Item = (Point, Direction);
+
+    fn next(&mut self) -> Option<(Point, Direction)> {
+        loop {
+            let next = self.current.step(self.direction);
+            if Some(next) == self.obstacle {
+                self.direction = self.direction.turn_right();
+                continue;
+            }
+            match self.grid.get(next) {
+                Some(Cell::Obstacle) => self.direction = self.direction.turn_right(),
+                Some(_) => {
+                    if let Some((min, max)) = self.limits {
+                        if !(min.row..=max.row).contains(&next.row)
+                            || !(min.col..=max.col).contains(&next.col)
+                        {
+                            return None;
+                        }
+                    }
+                    self.current = next;
+                    return Some((self.current, self.direction));
+                }
+                None => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cell, Facing, PatrolMap};
+    use aoc_common::differential::Shrink;
+    use aoc_common::property::Property;
+    use aoc_common::{Cells, Direction, Grid, Point, Rng, SparseGrid};
+
+    use super::EXAMPLE_INPUT;
+
+    #[test]
+    fn test_find_start() {
+        let grid = crate::parse_map(EXAMPLE_INPUT);
+        let start = grid.find_start();
+        assert_eq!(start, Some((Point::new(6, 4), Direction::Up)));
+    }
+
+    #[test]
+    fn test_no_guard() {
+        let grid = crate::parse_map(
+            "..#
+#..
+",
+        );
+        assert_eq!(grid.find_start(), None);
+        assert_eq!(grid.count_positions(), 0);
+        assert_eq!(grid.count_obstacle_placements(), 0);
+        assert_eq!(crate::brute_force_obstacle_placements(&grid), 0);
+        assert_eq!(crate::picture(&grid).ansi(), "..#\n#..\n");
+    }
+
+    #[test]
+    fn test_count_positions() {
+        let grid = crate::parse_map(EXAMPLE_INPUT);
+        assert_eq!(grid.count_positions(), 41)
+    }
+
+    #[test]
+    fn test_count_obstacle_placements() {
+        let grid = crate::parse_map(EXAMPLE_INPUT);
+        assert_eq!(grid.count_obstacle_placements(), 6)
+    }
+
+    #[test]
+    fn test_parse_cells() {
+        let grid = crate::parse_map(EXAMPLE_INPUT);
+        assert_eq!(grid.get(Point::new(0, 4)), Some(&Cell::Obstacle));
+        assert_eq!(grid.get(Point::new(6, 4)), Some(&Cell::Guard(Facing::Up)));
+        assert_eq!(
+            grid.to_string(),
+            EXAMPLE_INPUT.trim_start().to_string() + "\n"
+        );
+        let err = "..#\n.x^\n".parse::<Grid<Cell>>().unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (2, 2, "x"));
+    }
+
+    #[test]
+    fn test_sparse() {
+        let grid = crate::parse_map(EXAMPLE_INPUT);
+        let sparse = SparseGrid::from_grid(&grid, Cell::Empty);
+        assert_eq!(sparse.find_start(), Some((Point::new(6, 4), Direction::Up)));
+        assert_eq!(sparse.count_positions(), 41);
+        assert_eq!(sparse.count_obstacle_placements(), 6);
+        // A map far too large to hold densely, with the guard turning once at an obstacle.
+        let mut sparse = SparseGrid::new(Some(Cell::Empty)).bounded(100_000, 100_000);
+        sparse.insert(Point::new(50_000, 50_000), Cell::Guard(Facing::Up));
+        sparse.insert(Point::new(10, 50_000), Cell::Obstacle);
+        assert_eq!(sparse.count_positions(), (50_000 - 10) + (100_000 - 50_001));
+    }
+
+    #[test]
+    fn test_infinite_map() {
+        let mut sparse = SparseGrid::new(Some(Cell::Empty));
+        sparse.insert(Point::new(0, 0), Cell::Guard(Facing::Up));
+        for obstacle in [Point::new(-5, 0), Point::new(-4, 4), Point::new(2, 3)] {
+            sparse.insert(obstacle, Cell::Obstacle);
+        }
+        // Up, right and down between the obstacles, then left until one step past column 0.
+        assert_eq!(sparse.count_positions(), 5 + 3 + 5 + 4);
+        // The same map, with room around it on every side, has the same placements.
+        let mut grid = Grid::new(20, 20, Cell::Empty);
+        for (point, &cell) in sparse.cells() {
+            grid[point + Point::new(10, 10)] = cell;
+        }
+        let placements = sparse.count_obstacle_placements();
+        assert_eq!(placements, grid.count_obstacle_placements());
+        assert_eq!(placements, crate::brute_force_obstacle_placements(&grid));
+        // Including one step past column 0, which turns the guard back up to the start.
+        assert!(placements >= 1);
+        // With nothing to turn the guard, they leave one step past where they start.
+        let mut sparse = SparseGrid::new(Some(Cell::Empty));
+        sparse.insert(Point::new(3, -2), Cell::Guard(Facing::Left));
+        assert_eq!(sparse.count_positions(), 2);
+        assert_eq!(sparse.count_obstacle_placements(), 0);
+    }
+
+    #[test]
+    fn test_picture() {
+        let grid = crate::parse_map("..#.\n.^.#\n");
+        let yellow = |c| format!("\x1b[30;43m{}\x1b[0m", c);
+        assert_eq!(
+            crate::picture(&grid).ansi(),
+            format!(".{}#.\n.\x1b[1;31m↑\x1b[0m.#\n", yellow('.'))
+        );
+    }
+
+    #[test]
+    fn test_brute_force_obstacle_placements() {
+        let grid = crate::parse_map(EXAMPLE_INPUT);
+        assert_eq!(crate::brute_force_obstacle_placements(&grid), 6)
+    }
+
+    #[test]
+    fn test_obstacle_placements_match_brute_force() {
+        crate::OBSTACLE_PLACEMENTS.check_with(
+            300,
+            |rng| {
+                let size = rng.range(3..=12) as usize;
+                crate::generator::map(rng, size).to_string()
+            },
+            |map| {
+                crate::generator::shrink_map(&crate::parse_map(map))
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect()
+            },
+        );
+    }
+
+    fn map((seed, size): &(u64, usize)) -> Grid<Cell> {
+        crate::generator::map(&mut Rng::new(*seed), *size)
+    }
+
+    /// Checks `property` on maps from `generator::map`. A 1 by 1 map can be a single obstacle with
+    /// nowhere for the guard, so maps are at least 2 by 2.
+    fn check_maps(property: Property<(u64, usize)>, cases: u64) {
+        property.check_with(
+            cases,
+            |rng| (rng.next_u64(), rng.range(2..=12) as usize),
+            |input| {
+                input
+                    .shrink()
+                    .into_iter()
+                    .filter(|&(_, size)| size > 1)
+                    .collect()
+            },
+        );
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        check_maps(
+            Property {
+                name: "parsing a printed map gives it back",
+                holds: |input| {
+                    let map = map(input);
+                    map.to_string().parse::<Grid<Cell>>() == Ok(map)
+                },
+            },
+            300,
+        );
+    }
+
+    #[test]
+    fn test_positions_are_open() {
+        check_maps(
+            Property {
+                name: "the guard only visits open positions on the map",
+                holds: |input| {
+                    let map = map(input);
+                    map.get_positions(true)
+                        .into_iter()
+                        .all(|point| matches!(map.get(point), Some(Cell::Empty | Cell::Guard(_))))
+                },
+            },
+            300,
+        );
+    }
+
+    #[test]
+    fn test_obstacles_are_on_the_path() {
+        check_maps(
+            Property {
+                name: "there are fewer obstacle placements than positions visited",
+                holds: |input| {
+                    let map = map(input);
+                    map.count_obstacle_placements() < map.count_positions()
+                },
+            },
+            300,
+        );
+    }
+}
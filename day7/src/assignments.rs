@@ -0,0 +1,454 @@
+//! Every way of making an equation true, for seeing how ambiguous an equation is.
+//!
+//! [`count`] counts the assignments of operators which combine the numbers left to right into
+//! the test value, and [`all`] yields each of them. Both remember how many assignments lead to
+//! the test value from each value part way through an equation, as the same value is often
+//! reached in several ways, and never follow an operator which can't lead to it.
+//!
+//! [`count_with`] and [`all_with`] apply the operators in order of precedence instead. Values
+//! part way through those equations say less about where they lead, so every assignment is tried,
+//! only abandoning values above the test value when the operators allow it.
+
+use crate::precedence::{self, Partial, Undo};
+use crate::{operator, Number, Operator, Precedence, Solver, Witness};
+use std::collections::HashMap;
+
+/// Returns how many assignments of the operators to the gaps between the nums combine them left
+/// to right into the target, as `operator::evaluate` does. Counts larger than `u64::MAX` are
+/// `u64::MAX`.
+pub fn count<N: Number, O: Operator<N>>(target: &N, nums: &[N], operators: &[O]) -> u64 {
+    let Some((first, rest)) = nums.split_first() else {
+        return 0;
+    };
+    Counter::new(Solver::new(target, rest, operators)).count(Some(first), rest)
+}
+
+/// Returns how many assignments of the operators combine the nums into the target, applied in
+/// order of precedence. Counts larger than `u64::MAX` are `u64::MAX`.
+pub fn count_with<N: Number, O: Operator<N>>(
+    target: &N,
+    nums: &[N],
+    operators: &[O],
+    precedence: Precedence<O>,
+) -> u64 {
+    match precedence {
+        Precedence::LeftToRight => count(target, nums, operators),
+        _ => all_with(target, nums, operators, precedence)
+            .fold(0, |count, _| count.saturating_add(1)),
+    }
+}
+
+/// Returns an iterator over every assignment of the operators which combines the nums left to
+/// right into the target, in the order of the operators, earliest gap first.
+pub fn all<'a, N: Number, O: Operator<N>>(
+    target: &'a N,
+    nums: &'a [N],
+    operators: &'a [O],
+) -> Assignments<'a, N, O> {
+    all_with(target, nums, operators, Precedence::LeftToRight)
+}
+
+/// Returns an iterator over every assignment of the operators which combines the nums into the
+/// target, applied in order of precedence, in the order of the operators, earliest gap first.
+pub fn all_with<'a, N: Number, O: Operator<N>>(
+    target: &'a N,
+    nums: &'a [N],
+    operators: &'a [O],
+    precedence: Precedence<O>,
+) -> Assignments<'a, N, O> {
+    let (first, rest) = nums.split_first().unwrap_or((target, &[]));
+    let (walk, done) = match precedence {
+        Precedence::LeftToRight => {
+            let mut counter = Counter::new(Solver::new(target, rest, operators));
+            let done = counter.count(Some(first), rest) == 0;
+            let path = Vec::new();
+            (Walk::LeftToRight { counter, path }, done)
+        }
+        _ => {
+            let prune = precedence::can_prune(operators, rest);
+            let partial = Partial::new(first);
+            let path = Vec::new();
+            let walk = Walk::Precedence {
+                precedence,
+                prune,
+                partial,
+                path,
+            };
+            (walk, prune && first > target)
+        }
+    };
+    Assignments {
+        target,
+        nums,
+        operators,
+        walk,
+        next_operator: 0,
+        done: nums.is_empty() || done,
+    }
+}
+
+/// The assignments of operators which make an equation true, returned by [`all`] and
+/// [`all_with`].
+pub struct Assignments<'a, N, O> {
+    target: &'a N,
+    nums: &'a [N],
+    operators: &'a [O],
+    walk: Walk<'a, N, O>,
+    /// The index of the next operator to try in the gap after the path.
+    next_operator: usize,
+    done: bool,
+}
+
+/// How `Assignments` walks through the operators.
+enum Walk<'a, N, O> {
+    /// Left to right, only choosing operators which can still lead to the target.
+    LeftToRight {
+        counter: Counter<'a, N, O>,
+        /// The index of each operator chosen so far, with the value after it, where a value of
+        /// `None` is too large for an `N`.
+        path: Vec<(usize, Option<N>)>,
+    },
+    /// In order of precedence, choosing every operator unless `prune` says the value is too
+    /// large.
+    Precedence {
+        precedence: Precedence<O>,
+        prune: bool,
+        partial: Partial<N, &'a O>,
+        /// The index of each operator chosen so far, with what choosing it changed.
+        path: Vec<(usize, Undo<N>)>,
+    },
+}
+
+impl<'a, N: Number, O: Operator<N>> Iterator for Assignments<'a, N, O> {
+    type Item = Witness<'a, N, O>;
+
+    fn next(&mut self) -> Option<Witness<'a, N, O>> {
+        while !self.done {
+            let rest = &self.nums[1..];
+            let gap = self.walk.len();
+            if gap == rest.len() {
+                let witness = self.walk.reaches(self.target).then(|| Witness {
+                    target: self.target,
+                    nums: self.nums,
+                    operators: self.walk.chosen(self.operators),
+                    precedence: self.walk.precedence(),
+                });
+                self.backtrack();
+                match witness {
+                    Some(witness) => return Some(witness),
+                    None => continue,
+                }
+            }
+            let (target, operators) = (self.target, self.operators);
+            match self.walk.choose(
+                target,
+                operators,
+                self.next_operator,
+                &self.nums[0],
+                &rest[gap..],
+            ) {
+                true => self.next_operator = 0,
+                false => self.backtrack(),
+            }
+        }
+        None
+    }
+}
+
+impl<N: Number, O: Operator<N>> Assignments<'_, N, O> {
+    /// Moves on to the operators after the last one chosen, or finishes if none was chosen.
+    fn backtrack(&mut self) {
+        match self.walk.pop() {
+            Some(i) => self.next_operator = i + 1,
+            None => self.done = true,
+        }
+    }
+}
+
+impl<'a, N: Number, O: Operator<N>> Walk<'a, N, O> {
+    /// Returns how many operators have been chosen.
+    fn len(&self) -> usize {
+        match self {
+            Walk::LeftToRight { path, .. } => path.len(),
+            Walk::Precedence { path, .. } => path.len(),
+        }
+    }
+
+    /// Returns the precedence the operators are applied in.
+    fn precedence(&self) -> Precedence<O> {
+        match self {
+            Walk::LeftToRight { .. } => Precedence::LeftToRight,
+            Walk::Precedence { precedence, .. } => *precedence,
+        }
+    }
+
+    /// Returns the operators chosen so far.
+    fn chosen(&self, operators: &'a [O]) -> Vec<&'a O> {
+        match self {
+            Walk::LeftToRight { path, .. } => path.iter().map(|&(i, _)| &operators[i]).collect(),
+            Walk::Precedence { path, .. } => path.iter().map(|&(i, _)| &operators[i]).collect(),
+        }
+    }
+
+    /// Returns whether the operators chosen for every gap make the target.
+    fn reaches(&self, target: &N) -> bool {
+        match self {
+            // Only operators which lead to the target are chosen.
+            Walk::LeftToRight { .. } => true,
+            Walk::Precedence { partial, .. } => partial.finish().flatten().as_ref() == Some(target),
+        }
+    }
+
+    /// Chooses the first operator from index `from` on for the gap before `nums[0]` which may
+    /// still lead to the target, returning whether there was one. `first` is the first number of
+    /// the equation.
+    fn choose(
+        &mut self,
+        target: &N,
+        operators: &'a [O],
+        from: usize,
+        first: &N,
+        nums: &[N],
+    ) -> bool {
+        let (n, rest) = nums.split_first().expect("there is a gap to fill");
+        match self {
+            Walk::LeftToRight { counter, path } => {
+                let value = match path.last() {
+                    Some((_, value)) => value.as_ref(),
+                    None => Some(first),
+                };
+                let chosen = (from..operators.len()).find_map(|i| {
+                    let next = operator::step(&operators[i], value, n)?;
+                    (counter.count(next.as_ref(), rest) > 0).then_some((i, next))
+                });
+                chosen.map(|chosen| path.push(chosen)).is_some()
+            }
+            Walk::Precedence {
+                precedence,
+                prune,
+                partial,
+                path,
+            } => {
+                for (i, operator) in operators.iter().enumerate().skip(from) {
+                    let binds = precedence.of(operator);
+                    let Some(undo) = partial.push_undoable(operator, binds, n) else {
+                        continue;
+                    };
+                    if *prune && partial.leftmost().is_none_or(|value| value > target) {
+                        partial.undo(undo);
+                        continue;
+                    }
+                    path.push((i, undo));
+                    return true;
+                }
+                false
+            }
+        }
+    }
+
+    /// Takes back the last operator chosen, returning its index, if one was.
+    fn pop(&mut self) -> Option<usize> {
+        match self {
+            Walk::LeftToRight { path, .. } => path.pop().map(|(i, _)| i),
+            Walk::Precedence { partial, path, .. } => {
+                let (i, undo) = path.pop()?;
+                partial.undo(undo);
+                Some(i)
+            }
+        }
+    }
+}
+
+/// Counts the assignments which lead to the target, remembering the count from each value with
+/// each number of nums left.
+struct Counter<'a, N, O> {
+    solver: Solver<'a, N, O>,
+    counts: HashMap<(usize, Option<N>), u64>,
+}
+
+impl<'a, N: Number, O: Operator<N>> Counter<'a, N, O> {
+    fn new(solver: Solver<'a, N, O>) -> Self {
+        Counter {
+            solver,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Returns how many assignments combine the value with the nums into the target, where a
+    /// value of `None` is too large for an `N`.
+    fn count(&mut self, value: Option<&N>, nums: &[N]) -> u64 {
+        let target = self.solver.target;
+        let Some((first, rest)) = nums.split_first() else {
+            return u64::from(value == Some(target));
+        };
+        if self.solver.prune && value.is_none_or(|value| value > target) {
+            return 0;
+        }
+        let key = (nums.len(), value.cloned());
+        if let Some(&count) = self.counts.get(&key) {
+            return count;
+        }
+        let mut count = 0u64;
+        for operator in self.solver.operators {
+            if let Some(next) = operator::step(operator, value, first) {
+                count = count.saturating_add(self.count(next.as_ref(), rest));
+            }
+        }
+        self.counts.insert(key, count);
+        count
+    }
+}
+
+/// Returns the same as `count_with`, by evaluating every assignment of operators in turn.
+pub fn brute_force_count<N: Number, O: Operator<N>>(
+    target: &N,
+    nums: &[N],
+    operators: &[O],
+    precedence: Precedence<O>,
+) -> u64 {
+    let Some((first, rest)) = nums.split_first() else {
+        return 0;
+    };
+    let count = operators.len();
+    (0..count.pow(rest.len() as u32))
+        .filter(|&assignment| {
+            let mut choices = assignment;
+            let steps = rest.iter().map(|n| {
+                let operator = &operators[choices % count];
+                choices /= count;
+                (operator, n)
+            });
+            precedence::evaluate(precedence, first, steps).as_ref() == Some(target)
+        })
+        .count() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::{Builtin, PART1, PART2};
+    use crate::Precedence;
+    use aoc_common::differential::Differential;
+    use aoc_common::property::Property;
+
+    #[test]
+    fn test_count() {
+        assert_eq!(super::count(&3267usize, &[81, 40, 27], &PART1), 2);
+        assert_eq!(super::count(&7290usize, &[6, 8, 6, 15], &PART2), 1);
+        assert_eq!(super::count(&83usize, &[17, 5], &PART2), 0);
+        assert_eq!(super::count(&5usize, &[5], &PART1), 1);
+        assert_eq!(super::count(&5usize, &[], &PART1), 0);
+        // Any operators before multiplying by the zero work.
+        assert_eq!(super::count(&0usize, &[1, 1, 1, 1, 0], &PART2), 27);
+        let ones = [1usize; 42];
+        assert_eq!(super::count(&1, &ones, &[Builtin::Multiply]), 1);
+        assert_eq!(super::count(&1, &ones, &[Builtin::Multiply; 3]), u64::MAX);
+    }
+
+    #[test]
+    fn test_all() {
+        let all = |target: usize, nums: &[usize], operators| {
+            super::all(&target, nums, operators)
+                .map(|witness| witness.to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            all(3267, &[81, 40, 27], &PART1),
+            ["(81 + 40) * 27 = 3267", "81 * 40 + 27 = 3267"]
+        );
+        assert_eq!(
+            all(292, &[11, 6, 16, 20], &PART2),
+            ["(11 + 6) * 16 + 20 = 292"]
+        );
+        assert_eq!(all(83, &[17, 5], &PART2), Vec::<String>::new());
+        assert_eq!(all(5, &[5], &PART1), ["5 = 5"]);
+        assert_eq!(all(5, &[], &PART1), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_count_matches_brute_force() {
+        let count: Differential<(usize, Vec<usize>), u64> = Differential {
+            name: "count with every operator",
+            fast: |(target, nums)| super::count(target, nums, &Builtin::ALL),
+            reference: |(target, nums)| {
+                super::brute_force_count(target, nums, &Builtin::ALL, Precedence::LeftToRight)
+            },
+        };
+        count.check(1000, crate::generator::small_equation);
+    }
+
+    #[test]
+    fn test_count_with() {
+        let standard = |target: usize, nums: &[usize], operators| {
+            super::count_with(&target, nums, operators, Precedence::Standard)
+        };
+        assert_eq!(standard(1161, &[81, 40, 27], &PART1), 1);
+        assert_eq!(standard(3267, &[81, 40, 27], &PART1), 1);
+        assert_eq!(standard(3267, &[81, 40, 27], &PART2), 1);
+        assert_eq!(standard(5, &[5], &PART1), 1);
+        assert_eq!(standard(5, &[], &PART1), 0);
+        // Multiplying by the zero no longer hides the additions before it.
+        assert_eq!(standard(0, &[1, 1, 1, 1, 0], &PART1), 1);
+        let with = |precedence| super::count_with(&3267usize, &[81, 40, 27], &PART1, precedence);
+        assert_eq!(with(Precedence::LeftToRight), 2);
+        assert_eq!(with(Precedence::Table(|_| 0)), 2);
+    }
+
+    #[test]
+    fn test_all_with() {
+        let standard = |target: usize, nums: &[usize], operators| {
+            super::all_with(&target, nums, operators, Precedence::Standard)
+                .map(|witness| witness.to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            standard(1161, &[81, 40, 27], &PART1),
+            ["81 + 40 * 27 = 1161"]
+        );
+        assert_eq!(
+            standard(3267, &[81, 40, 27], &PART1),
+            ["81 * 40 + 27 = 3267"]
+        );
+        assert_eq!(standard(4347, &[81, 40, 27], &PART1), Vec::<String>::new());
+        assert_eq!(standard(5, &[5], &PART1), ["5 = 5"]);
+    }
+
+    #[test]
+    fn test_count_with_matches_brute_force() {
+        let count: Differential<(usize, Vec<usize>), u64> = Differential {
+            name: "count_with standard precedence",
+            fast: |(target, nums)| {
+                super::count_with(target, nums, &Builtin::ALL, Precedence::Standard)
+            },
+            reference: |(target, nums)| {
+                super::brute_force_count(target, nums, &Builtin::ALL, Precedence::Standard)
+            },
+        };
+        count.check(1000, crate::generator::small_equation);
+    }
+
+    #[test]
+    fn test_all_are_counted() {
+        let counted: Property<(usize, Vec<usize>)> = Property {
+            name: "all yields as many distinct assignments as there are, each true",
+            holds: |(target, nums)| {
+                let witnesses: Vec<_> = super::all(target, nums, &PART2).collect();
+                let all_true = witnesses.iter().all(|witness| {
+                    let steps = witness.operators.iter().copied().zip(&nums[1..]);
+                    crate::operator::evaluate(&nums[0], steps) == Some(*target)
+                });
+                let mut distinct: Vec<String> = witnesses.iter().map(ToString::to_string).collect();
+                distinct.sort();
+                distinct.dedup();
+                all_true
+                    && distinct.len() == witnesses.len()
+                    && witnesses.len() as u64
+                        == super::brute_force_count(target, nums, &PART2, Precedence::LeftToRight)
+            },
+        };
+        counted.check_with(
+            1000,
+            |rng| crate::generator::equation(rng, 6),
+            crate::generator::shrink_equation,
+        );
+    }
+}
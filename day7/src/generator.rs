@@ -0,0 +1,177 @@
+//! Random day 7 inputs.
+
+use crate::operator::{self, Builtin};
+use crate::{precedence, Precedence};
+use aoc_common::differential::Shrink;
+use aoc_common::Rng;
+
+const MAX_TOTAL: usize = 1_000_000_000_000_000;
+
+/// A test value and its numbers.
+pub type Equation = (usize, Vec<usize>);
+
+/// Generates `lines` equations like `equation`, with up to twelve numbers each.
+pub fn generate(seed: u64, lines: usize) -> String {
+    generate_with(seed, lines, Precedence::LeftToRight)
+}
+
+/// Generates `lines` equations like `equation_with`, with up to twelve numbers each.
+pub fn generate_with(seed: u64, lines: usize, precedence: Precedence<Builtin>) -> String {
+    let mut rng = Rng::new(seed);
+    let mut out = String::new();
+    for _ in 0..lines {
+        let (total, nums) = equation_with(&mut rng, 12, precedence);
+        let nums: Vec<String> = nums.iter().map(usize::to_string).collect();
+        out += &format!("{}: {}\n", total, nums.join(" "));
+    }
+    out
+}
+
+/// Generates an equation of two to `max_len` numbers from 1 to 999. The test value is made by
+/// combining the numbers left to right with random operators, then half the time it is nudged so
+/// it may no longer be reachable. Like real inputs, test values are kept below 10^15, so the sum
+/// of thousands of them still fits in 64 bits.
+pub fn equation(rng: &mut Rng, max_len: usize) -> Equation {
+    equation_with(rng, max_len, Precedence::LeftToRight)
+}
+
+/// Generates an equation like `equation`, but with the operators applied in order of precedence.
+pub fn equation_with(rng: &mut Rng, max_len: usize, precedence: Precedence<Builtin>) -> Equation {
+    loop {
+        let len = rng.range(2..=max_len as i64) as usize;
+        let nums: Vec<usize> = (0..len)
+            .map(|_| match rng.below(3) {
+                0 => rng.range(1..=9),
+                1 => rng.range(10..=99),
+                _ => rng.range(100..=999),
+            } as usize)
+            .collect();
+        let operators: Vec<&Builtin> = (1..len).map(|_| rng.choose(&operator::PART2)).collect();
+        let steps = operators.into_iter().zip(&nums[1..]);
+        let total = precedence::evaluate(precedence, &nums[0], steps);
+        let Some(mut total) = total.filter(|&total| total < MAX_TOTAL) else {
+            continue;
+        };
+        if rng.chance(0.5) {
+            total += rng.range(1..=10) as usize;
+        }
+        return (total, nums);
+    }
+}
+
+/// Generates an equation of one to five numbers below 6, including zeros, and a test value below
+/// 40. Operators which make values smaller get to bring them back to the test value, and there
+/// are few enough numbers to try every assignment of operators.
+pub fn small_equation(rng: &mut Rng) -> Equation {
+    let nums = (0..rng.range(1..=5))
+        .map(|_| rng.below(6) as usize)
+        .collect();
+    (rng.below(40) as usize, nums)
+}
+
+/// Returns simpler equations than `eq` which are still valid input, with at least one number.
+/// Each simpler list of numbers is paired with every test value it can reach, since a failure
+/// usually depends on the test value being reachable.
+pub fn shrink_equation(eq: &Equation) -> Vec<Equation> {
+    shrink_equation_with(Precedence::LeftToRight)(eq)
+}
+
+/// Returns a shrinker like `shrink_equation`, which pairs simpler lists of numbers with the test
+/// values they can reach with the operators applied in order of precedence.
+pub fn shrink_equation_with(
+    precedence: Precedence<Builtin>,
+) -> impl Fn(&Equation) -> Vec<Equation> {
+    move |eq| shrink(eq, precedence)
+}
+
+fn shrink(eq: &Equation, precedence: Precedence<Builtin>) -> Vec<Equation> {
+    let (target, nums) = eq;
+    let mut shrinks = Vec::new();
+    for nums in nums.shrink() {
+        if nums.is_empty() {
+            continue;
+        }
+        let mut totals = reachable_totals(&nums, precedence);
+        totals.sort_unstable();
+        totals.dedup();
+        shrinks.extend(totals.into_iter().map(|total| (total, nums.clone())));
+    }
+    shrinks.extend(
+        target
+            .shrink()
+            .into_iter()
+            .map(|target| (target, nums.clone())),
+    );
+    shrinks
+}
+
+/// Returns every value the numbers can be combined into with the part 2 operators, applied in
+/// order of precedence.
+fn reachable_totals(nums: &[usize], precedence: Precedence<Builtin>) -> Vec<usize> {
+    let assignments = nums[1..].iter().fold(vec![Vec::new()], |assignments, _| {
+        assignments
+            .into_iter()
+            .flat_map(|assignment: Vec<&Builtin>| {
+                operator::PART2.iter().map(move |operator| {
+                    let mut assignment = assignment.clone();
+                    assignment.push(operator);
+                    assignment
+                })
+            })
+            .collect()
+    });
+    assignments
+        .into_iter()
+        .filter_map(|assignment| {
+            let steps = assignment.into_iter().zip(&nums[1..]);
+            precedence::evaluate(precedence, &nums[0], steps)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::PART2;
+    use crate::{Day7, Precedence};
+    use aoc_common::Solution;
+
+    #[test]
+    fn test_generate() {
+        let input = super::generate(1, 100);
+        assert_eq!(input, super::generate(1, 100));
+        let eqs = Day7::parse(input.as_bytes()).unwrap();
+        assert_eq!(eqs.len(), 100);
+        let (sum, with_concatenation) = (Day7::part1(&eqs).unwrap(), Day7::part2(&eqs).unwrap());
+        assert!(0 < sum && sum < with_concatenation);
+    }
+
+    #[test]
+    fn test_shrink_equation() {
+        let shrinks = super::shrink_equation(&(190, vec![10, 19]));
+        assert!(shrinks.contains(&(19, vec![19])));
+        assert!(shrinks.contains(&(24, vec![5, 19])));
+        assert!(shrinks.contains(&(95, vec![10, 19])));
+        assert!(shrinks.iter().all(|(_, nums)| !nums.is_empty()));
+    }
+
+    #[test]
+    fn test_generate_with() {
+        let input = super::generate_with(1, 100, Precedence::Standard);
+        let eqs = Day7::parse(input.as_bytes()).unwrap();
+        assert_eq!(eqs.len(), 100);
+        let valid = eqs.iter().filter(|(target, nums)| {
+            crate::nums_total_up_with(target, nums, &PART2, Precedence::Standard)
+        });
+        // About half the test values are nudged away from the value of the equation.
+        assert!(valid.count() >= 25);
+    }
+
+    #[test]
+    fn test_shrink_equation_with() {
+        let eq = (0, vec![2, 3, 8]);
+        let shrinks = super::shrink_equation_with(Precedence::Standard)(&eq);
+        assert!(shrinks.contains(&(2 + 3 * 4, vec![2, 3, 4])));
+        assert!(shrinks.iter().all(|(_, nums)| !nums.is_empty()));
+        assert!(!super::shrink_equation(&eq).contains(&(2 + 3 * 4, vec![2, 3, 4])));
+    }
+}
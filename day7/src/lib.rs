@@ -0,0 +1,880 @@
+//! Day 7: Bridge Repair, which finds which equations can be made true by inserting operators.
+//!
+//! Equations are solved with any [`Number`] type: [`Day7`] uses `usize`, and [`Calibration`] can
+//! use a wider type for inputs whose test values don't fit.
+
+use aoc_common::differential::Differential;
+use aoc_common::parse::{numbered_lines, Token};
+use aoc_common::{InputError, Line, ParseError, Part, Solution};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::marker::PhantomData;
+
+pub mod assignments;
+pub mod generator;
+pub mod number;
+pub mod operator;
+pub mod precedence;
+
+#[cfg(feature = "bigint")]
+pub use num_bigint::BigUint;
+pub use number::Number;
+pub use operator::{Inverse, Operator};
+pub use precedence::Precedence;
+
+/// The [`Solution`] for day 7, solving equations of `N`s. A part whose sum of test values is too
+/// large for an `N` reports an [`Overflow`].
+pub struct Calibration<N>(PhantomData<N>);
+
+/// The [`Solution`] for day 7 with `usize` numbers, which real inputs fit in.
+pub type Day7 = Calibration<usize>;
+
+/// The example input from the puzzle description.
+pub const EXAMPLE_INPUT: &str = "
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+/// The error from a part whose sum of test values is too large for the number type it is solved
+/// with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// The name of the number type, as in `Number::NAME`.
+    pub number: &'static str,
+}
+
+impl std::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the sum of the test values of the true equations does not fit in {}",
+            self.number
+        )
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+impl<N: Number> Solution for Calibration<N> {
+    type Input = Vec<(N, Vec<N>)>;
+    type Answer = Result<N, Overflow>;
+
+    fn parse(r: impl std::io::BufRead) -> Result<Self::Input, InputError> {
+        get_number_vecs(r).collect()
+    }
+
+    fn part1(eqs: &Self::Input) -> Result<N, Overflow> {
+        Self::solve_with(eqs, Part::One, Precedence::LeftToRight)
+    }
+
+    fn part2(eqs: &Self::Input) -> Result<N, Overflow> {
+        Self::solve_with(eqs, Part::Two, Precedence::LeftToRight)
+    }
+}
+
+impl<N: Number> Calibration<N> {
+    /// Solves the given part with its operators applied in order of `precedence`, where `part1`
+    /// and `part2` apply them left to right as the puzzle does.
+    pub fn solve_with(
+        eqs: &[(N, Vec<N>)],
+        part: Part,
+        precedence: Precedence<operator::Builtin>,
+    ) -> Result<N, Overflow> {
+        let operators = operator::for_part(part);
+        sum_of_valid_equations(eqs, operators, Search::Backward, precedence)
+            .ok_or(Overflow { number: N::NAME })
+    }
+}
+
+fn get_number_vecs<N: Number>(
+    r: impl std::io::BufRead,
+) -> impl Iterator<Item = Result<(N, Vec<N>), InputError>> {
+    numbered_lines(r)
+        .skip_while(|line| matches!(line, Ok((_, text)) if text.is_empty()))
+        .take_while(|line| !matches!(line, Ok((_, text)) if text.is_empty()))
+        .map(|line| {
+            let (number, text) = line?;
+            Ok(line_to_equation(Line::new(number, &text))?)
+        })
+}
+
+fn line_to_equation<N: Number>(line: Line) -> Result<(N, Vec<N>), ParseError> {
+    let (first, rest) = line.split_once(':')?;
+    let total = parse_number(first.trim(), "a test value")?;
+    let nums = rest
+        .fields()
+        .map(|field| parse_number(field, "a number"))
+        .collect::<Result<_, _>>()?;
+    Ok((total, nums))
+}
+
+/// Parses `token` as an `N`, saying so if it is a number too large for one.
+fn parse_number<N: Number>(token: Token, expected: &str) -> Result<N, ParseError> {
+    token.parse(expected).map_err(|err| {
+        let digits = token.text.trim_start_matches('+');
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            token.error(&format!("{} that fits in {}", expected, N::NAME))
+        } else {
+            err
+        }
+    })
+}
+
+/// Returns whether the nums can be combined left to right into the target by inserting the
+/// operators between them, as `operator::evaluate` does.
+pub fn nums_total_up<N: Number, O: Operator<N>>(target: &N, nums: &[N], operators: &[O]) -> bool {
+    let Some((first, rest)) = nums.split_first() else {
+        return false;
+    };
+    Solver::new(target, rest, operators).reaches_target_from(Some(first), rest)
+}
+
+/// Returns whether the nums can be combined into the target by inserting the operators between
+/// them, applying the operators in order of precedence.
+pub fn nums_total_up_with<N: Number, O: Operator<N>>(
+    target: &N,
+    nums: &[N],
+    operators: &[O],
+    precedence: Precedence<O>,
+) -> bool {
+    match precedence {
+        Precedence::LeftToRight => nums_total_up(target, nums, operators),
+        _ => precedence::search(target, nums, operators, precedence).is_some(),
+    }
+}
+
+/// Returns which operators combine the nums left to right into the target, if any do.
+pub fn witness<'a, N: Number, O: Operator<N>>(
+    target: &'a N,
+    nums: &'a [N],
+    operators: &'a [O],
+) -> Option<Witness<'a, N, O>> {
+    witness_with(target, nums, operators, Precedence::LeftToRight)
+}
+
+/// Returns which operators combine the nums into the target, applied in order of precedence, if
+/// any do.
+pub fn witness_with<'a, N: Number, O: Operator<N>>(
+    target: &'a N,
+    nums: &'a [N],
+    operators: &'a [O],
+    precedence: Precedence<O>,
+) -> Option<Witness<'a, N, O>> {
+    if !matches!(precedence, Precedence::LeftToRight) {
+        return Some(Witness {
+            target,
+            nums,
+            operators: precedence::search(target, nums, operators, precedence)?,
+            precedence,
+        });
+    }
+    let (first, rest) = nums.split_first()?;
+    let solver = Solver::new(target, rest, operators);
+    let mut value = Some(first.clone());
+    if !solver.reaches_target_from(value.as_ref(), rest) {
+        return None;
+    }
+    // Each operator in turn is the first which still leads to the target.
+    let mut chosen = Vec::with_capacity(rest.len());
+    for (i, n) in rest.iter().enumerate() {
+        let (operator, next) = operators
+            .iter()
+            .find_map(|operator| {
+                let next = operator::step(operator, value.as_ref(), n)?;
+                solver
+                    .reaches_target_from(next.as_ref(), &rest[i + 1..])
+                    .then_some((operator, next))
+            })
+            .expect("the rest of the equation can be made true");
+        chosen.push(operator);
+        value = next;
+    }
+    Some(Witness {
+        target,
+        nums,
+        operators: chosen,
+        precedence,
+    })
+}
+
+/// The operators which make an equation true, displayed as the equation, such as
+/// `(81 + 40) * 27 = 3267`. Brackets show where the operators are applied in a different order to
+/// standard precedence, so the equation reads the same to anyone used to it.
+#[derive(Debug)]
+pub struct Witness<'a, N, O> {
+    pub target: &'a N,
+    pub nums: &'a [N],
+    /// The operator between each number and the next.
+    pub operators: Vec<&'a O>,
+    /// The order the operators are applied in.
+    pub precedence: Precedence<O>,
+}
+
+impl<N: Number, O: Operator<N>> std::fmt::Display for Witness<'_, N, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        precedence::write_equation(f, self.nums, &self.operators, self.precedence, self.target)
+    }
+}
+
+/// Returns a witness for each equation which can be made true with the operators, applied in
+/// order of precedence, in order.
+pub fn witnesses<'a, N: Number, O: Operator<N>>(
+    eqs: &'a [(N, Vec<N>)],
+    operators: &'a [O],
+    precedence: Precedence<O>,
+) -> impl Iterator<Item = Witness<'a, N, O>> {
+    eqs.iter()
+        .filter_map(move |(target, nums)| witness_with(target, nums, operators, precedence))
+}
+
+struct Solver<'a, N, O> {
+    target: &'a N,
+    operators: &'a [O],
+    /// Whether every operator grows with every number, so values can't come back down to the
+    /// target once they have passed it.
+    prune: bool,
+}
+
+impl<'a, N: Number, O: Operator<N>> Solver<'a, N, O> {
+    fn new(target: &'a N, nums: &[N], operators: &'a [O]) -> Self {
+        Solver {
+            target,
+            operators,
+            prune: nums
+                .iter()
+                .all(|n| operators.iter().all(|operator| operator.grows(n))),
+        }
+    }
+
+    /// Returns whether the value can be combined with the nums into the target, where a value of
+    /// `None` is too large for an `N`.
+    fn reaches_target_from(&self, value: Option<&N>, nums: &[N]) -> bool {
+        match value {
+            Some(value) if self.prune => {
+                value <= self.target && self.reaches_target_below(value, nums)
+            }
+            Some(value) => self.reaches_target(value, nums),
+            None => !self.prune && self.reaches_target_when_too_large(nums),
+        }
+    }
+
+    /// Returns whether the current value can be combined with the nums into the target, when
+    /// values can't come back down to the target once they have passed it.
+    fn reaches_target_below(&self, current: &N, nums: &[N]) -> bool {
+        let Some((first, rest)) = nums.split_first() else {
+            return current == self.target;
+        };
+        self.operators.iter().any(|operator| {
+            operator.apply(current, first).is_some_and(|value| {
+                value <= *self.target && self.reaches_target_below(&value, rest)
+            })
+        })
+    }
+
+    /// Returns whether the current value can be combined with the nums into the target.
+    /// Values too large for an `N` are followed by `reaches_target_when_too_large`.
+    fn reaches_target(&self, current: &N, nums: &[N]) -> bool {
+        let Some((first, rest)) = nums.split_first() else {
+            return current == self.target;
+        };
+        let mut too_large = false;
+        for operator in self.operators {
+            match operator.apply(current, first) {
+                Some(value) => {
+                    if self.reaches_target(&value, rest) {
+                        return true;
+                    }
+                }
+                None => too_large |= operator.grows(first),
+            }
+        }
+        too_large && self.reaches_target_when_too_large(rest)
+    }
+
+    /// Returns whether a value too large for an `N` can still be combined with the nums into the
+    /// target, through operators which give a constant.
+    fn reaches_target_when_too_large(&self, nums: &[N]) -> bool {
+        let Some((first, rest)) = nums.split_first() else {
+            return false;
+        };
+        let mut too_large = false;
+        for operator in self.operators {
+            match operator.constant(first) {
+                Some(value) => {
+                    if self.reaches_target(&value, rest) {
+                        return true;
+                    }
+                }
+                None => too_large |= operator.grows(first),
+            }
+        }
+        too_large && self.reaches_target_when_too_large(rest)
+    }
+}
+
+/// Returns the same as `nums_total_up`, working backwards from the target: each operator in the
+/// last gap is undone, leaving the value the nums before it must combine into. This prunes far
+/// more than working forwards, as most operators can't be undone for most values, such as
+/// division which isn't exact. Operators which can't say what undoing them gives are solved
+/// forwards from there.
+pub fn nums_total_up_backward<N: Number, O: Operator<N>>(
+    target: &N,
+    nums: &[N],
+    operators: &[O],
+) -> bool {
+    let Some((last, init)) = nums.split_last() else {
+        return false;
+    };
+    if init.is_empty() {
+        return last == target;
+    }
+    for operator in operators {
+        match operator.invert(target, last) {
+            Inverse::Impossible => {}
+            Inverse::Unique(value) => {
+                if nums_total_up_backward(&value, init, operators) {
+                    return true;
+                }
+            }
+            Inverse::Unknown => return nums_total_up(target, nums, operators),
+        }
+    }
+    false
+}
+
+/// Which way `sum_of_valid_equations` solves each equation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Search {
+    /// From the first number, with `nums_total_up`.
+    Forward,
+    /// From the test value, with `nums_total_up_backward`.
+    Backward,
+}
+
+impl Search {
+    /// Returns whether the nums can be combined into the target with the operators, applied in
+    /// order of precedence. Only equations applied left to right can be solved backwards, so
+    /// others are always solved forwards.
+    pub fn nums_total_up<N: Number, O: Operator<N>>(
+        self,
+        target: &N,
+        nums: &[N],
+        operators: &[O],
+        precedence: Precedence<O>,
+    ) -> bool {
+        match (self, precedence) {
+            (Search::Backward, Precedence::LeftToRight) => {
+                nums_total_up_backward(target, nums, operators)
+            }
+            _ => nums_total_up_with(target, nums, operators, precedence),
+        }
+    }
+}
+
+/// `nums_total_up_backward` with the part 2 operators, checked against `nums_total_up`.
+pub const NUMS_TOTAL_UP_BACKWARD: Differential<(usize, Vec<usize>), bool> = Differential {
+    name: "nums_total_up_backward",
+    fast: |(target, nums)| nums_total_up_backward(target, nums, &operator::PART2),
+    reference: |(target, nums)| nums_total_up(target, nums, &operator::PART2),
+};
+
+/// `nums_total_up` with the part 1 operators, checked against `brute_force_nums_total_up`.
+pub const NUMS_TOTAL_UP: Differential<(usize, Vec<usize>), bool> = Differential {
+    name: "nums_total_up",
+    fast: |(target, nums)| nums_total_up(target, nums, &operator::PART1),
+    reference: |(target, nums)| brute_force_nums_total_up(target, nums, &operator::PART1),
+};
+
+/// `nums_total_up` with the part 2 operators, checked against `brute_force_nums_total_up`.
+pub const NUMS_TOTAL_UP_WITH_CONCATENATION: Differential<(usize, Vec<usize>), bool> =
+    Differential {
+        name: "nums_total_up with concatenation",
+        fast: |(target, nums)| nums_total_up(target, nums, &operator::PART2),
+        reference: |(target, nums)| brute_force_nums_total_up(target, nums, &operator::PART2),
+    };
+
+/// Returns the same as `nums_total_up`, by evaluating every assignment of operators in turn.
+pub fn brute_force_nums_total_up<N: Number, O: Operator<N>>(
+    target: &N,
+    nums: &[N],
+    operators: &[O],
+) -> bool {
+    brute_force_nums_total_up_with(target, nums, operators, Precedence::LeftToRight)
+}
+
+/// Returns the same as `nums_total_up_with`, by evaluating every assignment of operators in turn.
+pub fn brute_force_nums_total_up_with<N: Number, O: Operator<N>>(
+    target: &N,
+    nums: &[N],
+    operators: &[O],
+    precedence: Precedence<O>,
+) -> bool {
+    let Some((first, rest)) = nums.split_first() else {
+        return false;
+    };
+    let count = operators.len();
+    (0..count.pow(rest.len() as u32)).any(|assignment| {
+        let mut choices = assignment;
+        let steps = rest.iter().map(|n| {
+            let operator = &operators[choices % count];
+            choices /= count;
+            (operator, n)
+        });
+        precedence::evaluate(precedence, first, steps).as_ref() == Some(target)
+    })
+}
+
+/// Returns the sum of the test values of the equations which can be made true with the
+/// operators, applied in order of precedence, solving them with `search`, or `None` if the sum
+/// overflows. With the `parallel` feature, the equations are checked across all cores.
+pub fn sum_of_valid_equations<N: Number, O: Operator<N>>(
+    eqs: &[(N, Vec<N>)],
+    operators: &[O],
+    search: Search,
+    precedence: Precedence<O>,
+) -> Option<N> {
+    let is_valid =
+        |(target, nums): &&(N, Vec<N>)| search.nums_total_up(target, nums, operators, precedence);
+    // Rayon's sums can't stop at the first overflow, so the valid equations are gathered first.
+    #[cfg(feature = "parallel")]
+    let mut valid = eqs
+        .par_iter()
+        .filter(is_valid)
+        .collect::<Vec<_>>()
+        .into_iter();
+    #[cfg(not(feature = "parallel"))]
+    let mut valid = eqs.iter().filter(is_valid);
+    valid.try_fold(N::from(0), |sum, (target, _)| sum.checked_add(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generator::Equation;
+    use crate::operator::{Builtin, PART1, PART2};
+    use crate::precedence::add_first;
+    use crate::{Operator, Precedence, Search};
+    use aoc_common::differential::Shrink;
+    use aoc_common::property::Property;
+    use aoc_common::Solution;
+
+    use super::EXAMPLE_INPUT;
+
+    fn total_up(target: usize, nums: &[usize], operators: &[impl Operator<usize>]) -> bool {
+        crate::nums_total_up(&target, nums, operators)
+    }
+
+    #[test]
+    fn test_get_number_vecs() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let num_vecs = crate::get_number_vecs::<usize>(test_input);
+        let mut num_vecs = num_vecs.map(Result::unwrap);
+        assert_eq!(num_vecs.next(), Some((190, vec![10, 19])));
+        assert_eq!(num_vecs.next(), Some((3267, vec![81, 40, 27])));
+        assert_eq!(num_vecs.next(), Some((83, vec![17, 5])));
+        assert_eq!(num_vecs.next(), Some((156, vec![15, 6])));
+        assert_eq!(num_vecs.next(), Some((7290, vec![6, 8, 6, 15])));
+        assert_eq!(num_vecs.next(), Some((161011, vec![16, 10, 13])));
+        assert_eq!(num_vecs.next(), Some((192, vec![17, 8, 14])));
+        assert_eq!(num_vecs.next(), Some((21037, vec![9, 7, 18, 13])));
+        assert_eq!(num_vecs.next(), Some((292, vec![11, 6, 16, 20])));
+        assert_eq!(num_vecs.next(), None);
+    }
+
+    #[test]
+    fn test_line_to_equation_errors() {
+        let err = crate::line_to_equation::<usize>(aoc_common::Line::new(5, "3267 81 40 27"))
+            .unwrap_err();
+        assert_eq!((err.line, err.column, err.token.as_str()), (5, 14, ""));
+        assert_eq!(err.expected, "`:`");
+        let err =
+            crate::line_to_equation::<usize>(aoc_common::Line::new(1, " 3x67: 81")).unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (2, "3x67"));
+        assert_eq!(err.expected, "a test value");
+        let err = crate::line_to_equation::<usize>(aoc_common::Line::new(1, "3267: 81 -40 27"))
+            .unwrap_err();
+        assert_eq!((err.column, err.token.as_str()), (10, "-40"));
+        assert_eq!(err.expected, "a number");
+    }
+
+    #[test]
+    fn test_nums_total_up() {
+        assert_eq!(total_up(190, &[10, 19], &PART1), true);
+        assert_eq!(total_up(3267, &[81, 40, 27], &PART1), true);
+        assert_eq!(total_up(83, &[17, 5], &PART1), false);
+        assert_eq!(total_up(156, &[15, 6], &PART1), false);
+        assert_eq!(total_up(7290, &[6, 8, 6, 15], &PART1), false);
+        assert_eq!(total_up(161011, &[16, 10, 13], &PART1), false);
+        assert_eq!(total_up(192, &[17, 8, 14], &PART1), false);
+        assert_eq!(total_up(21037, &[9, 7, 18, 13], &PART1), false);
+        assert_eq!(total_up(292, &[11, 6, 16, 20], &PART1), true);
+        // The case which is erroneously "correct" if you pretend there's a leading 0
+        assert_eq!(total_up(103, &[3, 1, 1, 5, 98], &PART1), false);
+    }
+
+    #[test]
+    fn test_nums_total_up_with_concatenation() {
+        assert_eq!(total_up(190, &[10, 19], &PART2), true);
+        assert_eq!(total_up(3267, &[81, 40, 27], &PART2), true);
+        assert_eq!(total_up(83, &[17, 5], &PART2), false);
+        assert_eq!(total_up(156, &[15, 6], &PART2), true);
+        assert_eq!(total_up(7290, &[6, 8, 6, 15], &PART2), true);
+        assert_eq!(total_up(161011, &[16, 10, 13], &PART2), false);
+        assert_eq!(total_up(192, &[17, 8, 14], &PART2), true);
+        assert_eq!(total_up(21037, &[9, 7, 18, 13], &PART2), false);
+        assert_eq!(total_up(292, &[11, 6, 16, 20], &PART2), true);
+        // The case which is erroneously "correct" if you pretend there's a leading 0
+        assert_eq!(total_up(103, &[3, 1, 1, 5, 98], &PART2), false);
+    }
+
+    #[test]
+    fn test_concatenation_applies_to_value_so_far() {
+        // 6 * 8 || 6 * 15: the 6 is concatenated onto 48, not onto the 8 before it.
+        assert!(total_up(486, &[6, 8, 6], &PART2));
+        assert!(total_up(7290, &[6, 8, 6, 15], &PART2));
+        assert!(!total_up(486, &[6, 8, 6], &PART1));
+    }
+
+    #[test]
+    fn test_sum_of_valid_equations() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let eqs: Vec<(usize, Vec<usize>)> = crate::get_number_vecs::<usize>(test_input)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for search in [Search::Forward, Search::Backward] {
+            let result =
+                crate::sum_of_valid_equations(&eqs, &PART1, search, Precedence::LeftToRight);
+            assert_eq!(result, Some(3749));
+        }
+    }
+
+    #[test]
+    fn test_sum_of_valid_equations_with_concatenation() {
+        let test_input = std::io::BufReader::new(EXAMPLE_INPUT.as_bytes());
+        let eqs: Vec<(usize, Vec<usize>)> = crate::get_number_vecs::<usize>(test_input)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        for search in [Search::Forward, Search::Backward] {
+            let result =
+                crate::sum_of_valid_equations(&eqs, &PART2, search, Precedence::LeftToRight);
+            assert_eq!(result, Some(11387));
+        }
+    }
+
+    #[test]
+    fn test_nums_total_up_overflow() {
+        assert!(!total_up(usize::MAX - 1, &[usize::MAX / 2, 3, 2], &PART2));
+        assert!(total_up(usize::MAX, &[usize::MAX / 2, 2, 1], &PART1));
+    }
+
+    #[test]
+    fn test_nums_total_up_matches_brute_force() {
+        let generate = |rng: &mut aoc_common::Rng| crate::generator::equation(rng, 7);
+        let shrink = crate::generator::shrink_equation;
+        crate::NUMS_TOTAL_UP.check_with(2000, generate, shrink);
+        crate::NUMS_TOTAL_UP_WITH_CONCATENATION.check_with(2000, generate, shrink);
+    }
+
+    #[test]
+    fn test_part1_implies_part2() {
+        let implies: Property<(usize, Vec<usize>)> = Property {
+            name: "equations true without concatenation are true with it",
+            holds: |(target, nums)| {
+                !crate::nums_total_up(target, nums, &PART1)
+                    || crate::nums_total_up(target, nums, &PART2)
+            },
+        };
+        implies.check_with(
+            2000,
+            |rng| crate::generator::equation(rng, 7),
+            crate::generator::shrink_equation,
+        );
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        let round_trip: Property<(usize, Vec<usize>)> = Property {
+            name: "parsing a printed equation gives it back",
+            holds: |(target, nums)| {
+                let nums_text: Vec<String> = nums.iter().map(ToString::to_string).collect();
+                let text = format!("{}: {}", target, nums_text.join(" "));
+                crate::Day7::parse(text.as_bytes()).unwrap() == [(*target, nums.clone())]
+            },
+        };
+        round_trip.check_with(
+            500,
+            |rng| crate::generator::equation(rng, 12),
+            crate::generator::shrink_equation,
+        );
+    }
+
+    #[test]
+    fn test_zeros() {
+        assert!(total_up(0, &[100, 5, 0], &PART1));
+        assert!(total_up(7, &[100, 5, 0, 7], &PART1));
+        assert!(total_up(1, &[usize::MAX, 2, 0, 1], &PART1));
+        assert!(total_up(120, &[12, 0], &PART2));
+        assert!(!total_up(120, &[12, 0], &PART1));
+        assert!(total_up(5, &[0, 5], &PART2));
+        assert!(total_up(0, &[0], &PART1));
+    }
+
+    #[test]
+    fn test_zeros_match_brute_force() {
+        // Small numbers and test values, so that zeros are common and many equations are true.
+        let generate = crate::generator::small_equation;
+        crate::NUMS_TOTAL_UP.check(2000, generate);
+        crate::NUMS_TOTAL_UP_WITH_CONCATENATION.check(2000, generate);
+    }
+
+    #[test]
+    fn test_number_types() {
+        let input = "36893488147419103232: 18446744073709551616 2\n1: 1\n";
+        let err = crate::Day7::parse(input.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string().lines().next(),
+            Some("expected a test value that fits in usize, found `36893488147419103232`")
+        );
+        let eqs = crate::Calibration::<u128>::parse(input.as_bytes()).unwrap();
+        assert_eq!(
+            crate::Calibration::<u128>::part1(&eqs),
+            Ok(36893488147419103233)
+        );
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        // A test value near the limit only matters if its equation is true.
+        let input = format!("{}: 1 2\n3: 1 2\n2: 1 2\n", u64::MAX);
+        let eqs = crate::Calibration::<u64>::parse(input.as_bytes()).unwrap();
+        assert_eq!(crate::Calibration::<u64>::part1(&eqs), Ok(5));
+        let input = format!("{}: {} 1\n3: 1 2\n2: 1 2\n", u64::MAX, u64::MAX);
+        let eqs = crate::Calibration::<u64>::parse(input.as_bytes()).unwrap();
+        let err = crate::Calibration::<u64>::part1(&eqs).unwrap_err();
+        assert_eq!(err, crate::Overflow { number: "u64" });
+        assert_eq!(
+            err.to_string(),
+            "the sum of the test values of the true equations does not fit in u64"
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_numbers() {
+        use num_bigint::BigUint;
+        let big: BigUint = u128::MAX.into();
+        let input = format!("{}0: {} 0\n{}: {} 1\n", big, big, big.clone() + 1u8, big);
+        let eqs = crate::Calibration::<BigUint>::parse(input.as_bytes()).unwrap();
+        let both = big.clone() * 10u8 + &big + 1u8;
+        assert_eq!(crate::Calibration::<BigUint>::part1(&eqs), Ok(big + 1u8));
+        assert_eq!(crate::Calibration::<BigUint>::part2(&eqs), Ok(both));
+    }
+
+    #[test]
+    fn test_operators() {
+        assert!(total_up(
+            1,
+            &[10, 3, 4],
+            &[Builtin::Subtract, Builtin::Divide]
+        ));
+        assert!(!total_up(1, &[10, 3, 4], &PART2));
+        assert!(total_up(6, &[12, 10], &[Builtin::Xor]));
+        assert!(total_up(1, &[usize::MAX, 2, 0, 1], &Builtin::ALL));
+        assert!(!total_up(1, &[usize::MAX, 2, 1, 0], &[Builtin::Subtract]));
+    }
+
+    #[test]
+    fn test_custom_operator() {
+        /// The larger of the two numbers.
+        struct Max;
+
+        impl Operator<usize> for Max {
+            fn apply(&self, a: &usize, b: &usize) -> Option<usize> {
+                Some(*a.max(b))
+            }
+
+            fn symbol(&self) -> &str {
+                "max"
+            }
+
+            fn grows(&self, _b: &usize) -> bool {
+                true
+            }
+        }
+
+        assert!(total_up(20, &[3, 20, 4], &[Max]));
+        let max_or_add: [&dyn Operator<usize>; 2] = [&Max, &Builtin::Add];
+        assert!(total_up(24, &[3, 20, 4], &max_or_add));
+        assert!(!total_up(4, &[3, 20, 4], &[Max]));
+        let witness = crate::witness(&24, &[3, 20, 4], &max_or_add).unwrap();
+        assert_eq!(witness.to_string(), "(3 max 20) + 4 = 24");
+    }
+
+    #[test]
+    fn test_backward() {
+        let backward = |target: usize, nums: &[usize], operators: &[Builtin]| {
+            crate::nums_total_up_backward(&target, nums, operators)
+        };
+        assert!(backward(3267, &[81, 40, 27], &PART1));
+        assert!(backward(7290, &[6, 8, 6, 15], &PART2));
+        assert!(!backward(7290, &[6, 8, 6, 15], &PART1));
+        assert!(!backward(83, &[17, 5], &PART2));
+        assert!(backward(0, &[usize::MAX, 2, 0], &PART1));
+        assert!(!backward(103, &[3, 1, 1, 5, 98], &PART2));
+        assert!(backward(1, &[10, 3, 4], &Builtin::ALL));
+        assert!(!backward(5, &[], &PART1));
+    }
+
+    #[test]
+    fn test_backward_matches_forward() {
+        crate::NUMS_TOTAL_UP_BACKWARD.check_with(
+            2000,
+            |rng| crate::generator::equation(rng, 12),
+            crate::generator::shrink_equation,
+        );
+    }
+
+    #[test]
+    fn test_witness() {
+        let witness = |target: usize, nums: &[usize], operators: &[Builtin]| {
+            crate::witness(&target, nums, operators).map(|witness| witness.to_string())
+        };
+        // Without the brackets this would read as 1161, with standard precedence.
+        assert_eq!(
+            witness(3267, &[81, 40, 27], &PART1).as_deref(),
+            Some("(81 + 40) * 27 = 3267")
+        );
+        assert_eq!(
+            witness(7290, &[6, 8, 6, 15], &PART2).as_deref(),
+            Some("(6 * 8) || 6 * 15 = 7290")
+        );
+        assert_eq!(witness(83, &[17, 5], &PART2), None);
+        assert_eq!(witness(5, &[5], &PART1).as_deref(), Some("5 = 5"));
+        assert_eq!(
+            witness(0, &[usize::MAX, 2, 0], &PART1).as_deref(),
+            Some("(18446744073709551615 + 2) * 0 = 0")
+        );
+        assert_eq!(
+            witness(1, &[10, 3, 4], &Builtin::ALL).as_deref(),
+            Some("(10 - 3) / 4 = 1")
+        );
+    }
+
+    #[test]
+    fn test_witnesses() {
+        let eqs = crate::Day7::parse(EXAMPLE_INPUT.as_bytes()).unwrap();
+        let witnesses: Vec<String> = crate::witnesses(&eqs, &PART1, Precedence::LeftToRight)
+            .map(|witness| witness.to_string())
+            .collect();
+        assert_eq!(
+            witnesses,
+            [
+                "10 * 19 = 190",
+                "(81 + 40) * 27 = 3267",
+                "(11 + 6) * 16 + 20 = 292"
+            ]
+        );
+        assert_eq!(
+            crate::witnesses(&eqs, &PART2, Precedence::LeftToRight).count(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_witnesses_evaluate_to_target() {
+        let witnessed: Property<(usize, Vec<usize>)> = Property {
+            name: "true equations have a witness, which evaluates to the test value",
+            holds: |(target, nums)| {
+                let witness = crate::witness(target, nums, &Builtin::ALL);
+                let evaluated = witness.as_ref().map(|witness| {
+                    let steps = witness.operators.iter().copied().zip(&nums[1..]);
+                    crate::operator::evaluate(&nums[0], steps)
+                });
+                witness.is_some() == total_up(*target, nums, &Builtin::ALL)
+                    && evaluated.is_none_or(|value| value == Some(*target))
+            },
+        };
+        witnessed.check(1000, crate::generator::small_equation);
+    }
+
+    #[test]
+    fn test_precedence() {
+        let witness = |target: usize, nums: &[usize], precedence| {
+            crate::witness_with(&target, nums, &PART2, precedence).map(|w| w.to_string())
+        };
+        assert_eq!(
+            witness(1161, &[81, 40, 27], Precedence::Standard).as_deref(),
+            Some("81 + 40 * 27 = 1161")
+        );
+        assert_eq!(witness(1161, &[81, 40, 27], Precedence::LeftToRight), None);
+        assert_eq!(
+            witness(14, &[2, 3, 4], Precedence::Table(add_first)).as_deref(),
+            Some("2 * (3 + 4) = 14")
+        );
+        assert_eq!(
+            witness(3267, &[81, 40, 27], Precedence::Table(add_first)).as_deref(),
+            Some("(81 + 40) * 27 = 3267")
+        );
+        assert!(crate::nums_total_up_with(
+            &1161usize,
+            &[81, 40, 27],
+            &PART1,
+            Precedence::Standard
+        ));
+        assert!(!crate::nums_total_up_with(
+            &1161usize,
+            &[81, 40, 27],
+            &PART1,
+            Precedence::LeftToRight
+        ));
+    }
+
+    /// A way of solving equations, the operators to solve them with, and an equation.
+    type ModeCase = ((Search, Precedence<Builtin>, &'static [Builtin]), Equation);
+
+    /// `Search::nums_total_up` in some mode, checked against `brute_force_nums_total_up_with`.
+    const MODE: crate::Differential<ModeCase, bool> = crate::Differential {
+        name: "Search::nums_total_up",
+        fast: |((search, precedence, operators), (target, nums))| {
+            search.nums_total_up(target, nums, operators, *precedence)
+        },
+        reference: |((_, precedence, operators), (target, nums))| {
+            crate::brute_force_nums_total_up_with(target, nums, operators, *precedence)
+        },
+    };
+
+    /// Checks solving with `search` and `precedence` against the brute force, with the part 2
+    /// operators on generated equations and with every operator on small ones.
+    fn check_mode(search: Search, precedence: Precedence<Builtin>) {
+        let shrink = crate::generator::shrink_equation_with(precedence);
+        MODE.check_with(
+            500,
+            |rng| {
+                let eq = crate::generator::equation_with(rng, 6, precedence);
+                ((search, precedence, &PART2[..]), eq)
+            },
+            |(mode, eq)| shrink(eq).into_iter().map(|eq| (*mode, eq)).collect(),
+        );
+        MODE.check_with(
+            1000,
+            |rng| {
+                let eq = crate::generator::small_equation(rng);
+                ((search, precedence, &Builtin::ALL[..]), eq)
+            },
+            |(mode, eq)| eq.shrink().into_iter().map(|eq| (*mode, eq)).collect(),
+        );
+    }
+
+    #[test]
+    fn test_modes_match_brute_force() {
+        check_mode(Search::Forward, Precedence::LeftToRight);
+        check_mode(Search::Backward, Precedence::LeftToRight);
+        check_mode(Search::Forward, Precedence::Standard);
+        check_mode(Search::Forward, Precedence::Table(add_first));
+    }
+}
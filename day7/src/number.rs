@@ -0,0 +1,252 @@
+//! The number types equations can be solved with.
+//!
+//! Every operation is checked, so a value too large for the type is never mistaken for a small
+//! one. `usize` is plenty for real inputs; `u128` and, with the `bigint` feature, `BigUint` solve
+//! equations with larger test values.
+
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// An unsigned integer type which equations can be solved with.
+pub trait Number:
+    Clone + Ord + Hash + Debug + Display + FromStr + From<u8> + Send + Sync + 'static
+{
+    /// The name of the type, for error messages.
+    const NAME: &'static str;
+
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+    /// Returns `None` if `other` is larger, as there are no negative values.
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+
+    /// Returns the quotient rounded down, or `None` if `other` is zero.
+    fn checked_div(&self, other: &Self) -> Option<Self>;
+
+    fn xor(&self, other: &Self) -> Self;
+
+    /// Returns `self` to the power of `exponent`, where zero to the power of zero is one.
+    fn checked_pow(&self, exponent: &Self) -> Option<Self>;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::from(0)
+    }
+
+    /// Returns the digits of `self` followed by the digits of `other`, such as 12 and 345 giving
+    /// 12345, or 12 and 0 giving 120, or `None` if that overflows.
+    fn checked_concatenate(&self, other: &Self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(other.clone());
+        }
+        let ten = Self::from(10);
+        let mut shift = ten.clone();
+        while shift <= *other {
+            shift = shift.checked_mul(&ten)?;
+        }
+        self.checked_mul(&shift)?.checked_add(other)
+    }
+
+    /// Returns the number whose digits followed by the digits of `suffix` are `self`, undoing
+    /// `checked_concatenate`, such as 12345 and 345 giving 12, or `None` if there is none.
+    fn strip_suffix(&self, suffix: &Self) -> Option<Self> {
+        let prefix = self.checked_sub(suffix)?;
+        let ten = Self::from(10);
+        let mut shift = ten.clone();
+        while shift <= *suffix {
+            match shift.checked_mul(&ten) {
+                Some(next) => shift = next,
+                // Only zero is short enough to go in front of a suffix this long.
+                None => return prefix.is_zero().then_some(prefix),
+            }
+        }
+        let quotient = prefix.checked_div(&shift)?;
+        (quotient.checked_mul(&shift)? == prefix).then_some(quotient)
+    }
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const NAME: &'static str = stringify!($t);
+
+                #[inline]
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_add(*self, *other)
+                }
+
+                #[inline]
+                fn checked_mul(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_mul(*self, *other)
+                }
+
+                #[inline]
+                fn checked_sub(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_sub(*self, *other)
+                }
+
+                #[inline]
+                fn checked_div(&self, other: &Self) -> Option<Self> {
+                    <$t>::checked_div(*self, *other)
+                }
+
+                #[inline]
+                fn xor(&self, other: &Self) -> Self {
+                    self ^ other
+                }
+
+                fn checked_pow(&self, exponent: &Self) -> Option<Self> {
+                    match (*self, u32::try_from(*exponent)) {
+                        (_, Ok(exponent)) => <$t>::checked_pow(*self, exponent),
+                        (0 | 1, Err(_)) => Some(*self),
+                        (_, Err(_)) => None,
+                    }
+                }
+
+                #[inline]
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+
+                fn checked_concatenate(&self, other: &Self) -> Option<Self> {
+                    let digits = other.checked_ilog10().unwrap_or(0) + 1;
+                    match <$t>::checked_pow(10, digits) {
+                        Some(shift) => <$t>::checked_mul(*self, shift)?.checked_add(*other),
+                        None if *self == 0 => Some(*other),
+                        None => None,
+                    }
+                }
+
+                #[inline]
+                fn strip_suffix(&self, suffix: &Self) -> Option<Self> {
+                    let prefix = <$t>::checked_sub(*self, *suffix)?;
+                    let digits = suffix.checked_ilog10().unwrap_or(0) + 1;
+                    match <$t>::checked_pow(10, digits) {
+                        Some(shift) => (prefix % shift == 0).then_some(prefix / shift),
+                        None => (prefix == 0).then_some(0),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u64, u128, usize);
+
+#[cfg(feature = "bigint")]
+impl Number for num_bigint::BigUint {
+    const NAME: &'static str = "BigUint";
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        (self >= other).then(|| self - other)
+    }
+
+    fn checked_div(&self, other: &Self) -> Option<Self> {
+        (!other.is_zero()).then(|| self / other)
+    }
+
+    fn xor(&self, other: &Self) -> Self {
+        self ^ other
+    }
+
+    /// Results of more than 2^24 bits, 2 MiB each, are treated as too large to compute. No test
+    /// value short enough to read is that large.
+    fn checked_pow(&self, exponent: &Self) -> Option<Self> {
+        if *self <= Self::from(1u8) {
+            return Some(if exponent.is_zero() {
+                Self::from(1u8)
+            } else {
+                self.clone()
+            });
+        }
+        let exponent = u32::try_from(exponent).ok()?;
+        let bits = self.bits().checked_mul(exponent.into())?;
+        (bits <= 1 << 24).then(|| self.pow(exponent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Number;
+
+    #[test]
+    fn test_checked_concatenate() {
+        assert_eq!(123usize.checked_concatenate(&100), Some(123100));
+        assert_eq!(100usize.checked_concatenate(&123), Some(100123));
+        assert_eq!(1234usize.checked_concatenate(&99999), Some(123499999));
+        assert_eq!(99999usize.checked_concatenate(&1234), Some(999991234));
+        assert_eq!((usize::MAX / 10).checked_concatenate(&12), None);
+        assert_eq!(12usize.checked_concatenate(&0), Some(120));
+        assert_eq!(0u64.checked_concatenate(&u64::MAX), Some(u64::MAX));
+        assert_eq!(u64::MAX.checked_concatenate(&9), None);
+    }
+
+    #[test]
+    fn test_strip_suffix() {
+        assert_eq!(12345usize.strip_suffix(&345), Some(12));
+        assert_eq!(12345usize.strip_suffix(&45), Some(123));
+        assert_eq!(12345usize.strip_suffix(&12345), Some(0));
+        assert_eq!(12345usize.strip_suffix(&346), None);
+        assert_eq!(12345usize.strip_suffix(&99999), None);
+        assert_eq!(120usize.strip_suffix(&0), Some(12));
+        assert_eq!(u64::MAX.strip_suffix(&u64::MAX), Some(0));
+        assert_eq!(u64::MAX.strip_suffix(&(u64::MAX - 1)), None);
+        for a in 0u64..200 {
+            for b in 0..200 {
+                let c = a.checked_concatenate(&b).unwrap();
+                assert_eq!(c.strip_suffix(&b), Some(a));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide_numbers() {
+        let big = u64::MAX as u128;
+        assert_eq!(big.checked_concatenate(&9), Some(big * 10 + 9));
+        assert_eq!(Number::checked_add(&u128::MAX, &1), None);
+        assert_eq!(Number::checked_mul(&u128::MAX, &0), Some(0));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_numbers() {
+        use num_bigint::BigUint;
+        let big: BigUint = u128::MAX.into();
+        let concatenated = big.checked_concatenate(&BigUint::from(0u8)).unwrap();
+        assert_eq!(concatenated, big.clone() * 10u8);
+        assert_eq!(concatenated.to_string(), format!("{}0", u128::MAX));
+        assert_eq!(
+            BigUint::from(0u8).checked_concatenate(&big),
+            Some(big.clone())
+        );
+        assert_eq!(
+            BigUint::from(12u8).checked_concatenate(&BigUint::from(345u16)),
+            Some(BigUint::from(12345u16))
+        );
+        let two = BigUint::from(2u8);
+        assert_eq!(two.checked_pow(&BigUint::from(200u8)), Some(&two << 199u8));
+        assert_eq!(two.checked_pow(&big), None);
+        assert_eq!(BigUint::from(1u8).checked_pow(&big), Some(1u8.into()));
+        assert_eq!(
+            BigUint::from(0u8).checked_pow(&0u8.into()),
+            Some(1u8.into())
+        );
+        assert_eq!(two.checked_sub(&big), None);
+        assert_eq!(big.checked_div(&BigUint::from(0u8)), None);
+        assert_eq!(
+            concatenated.strip_suffix(&BigUint::from(0u8)),
+            Some(big.clone())
+        );
+        assert_eq!(big.strip_suffix(&big), Some(0u8.into()));
+    }
+}
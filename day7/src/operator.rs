@@ -0,0 +1,354 @@
+//! The operators which can be inserted between the numbers of an equation.
+//!
+//! Part 1 uses `+` and `*`, and part 2 adds concatenation, but the solver accepts any set of
+//! [`Operator`]s, such as the other [`Builtin`] ones. New ones only need to say how they combine
+//! two numbers, and how their result compares to the value on the left, which the solver uses to
+//! prune its search.
+
+use crate::Number;
+use aoc_common::Part;
+
+/// A way of combining the value so far with the next number of an equation.
+pub trait Operator<N>: Send + Sync {
+    /// Returns `a` combined with `b`, or `None` if that is undefined or too large for an `N`.
+    fn apply(&self, a: &N, b: &N) -> Option<N>;
+
+    /// How the operator is written between two numbers, such as `+`.
+    fn symbol(&self) -> &str;
+
+    /// Returns whether `a` combined with `b` is at least `a`, whatever `a` is. Values above the
+    /// test value are only abandoned when every operator grows with every remaining number, and
+    /// `apply` returning `None` when this is true means the result is too large.
+    fn grows(&self, _b: &N) -> bool {
+        false
+    }
+
+    /// Returns whether the operator grows with every number larger than one it grows with. When
+    /// every operator does, and grows with every remaining number, it also grows with the value
+    /// of the operators after it which bind more tightly, so values above the test value can be
+    /// abandoned whatever the precedence.
+    fn grows_with_larger(&self) -> bool {
+        false
+    }
+
+    /// Returns what any `a` combined with `b` gives, if that doesn't depend on `a`, as with
+    /// multiplying by zero. A value which became too large for an `N` part way through an
+    /// equation is only followed through operators which grow or give a constant.
+    fn constant(&self, _b: &N) -> Option<N> {
+        None
+    }
+
+    /// Returns how tightly the operator binds with standard precedence, where multiplication
+    /// binds more tightly than addition. Operators which don't say bind the least tightly.
+    fn precedence(&self) -> u8 {
+        0
+    }
+
+    /// Returns which `a` combined with `b` gives `c`, for solving equations backwards from the
+    /// test value. Operators which don't say are solved forwards.
+    fn invert(&self, _c: &N, _b: &N) -> Inverse<N> {
+        Inverse::Unknown
+    }
+}
+
+/// Which values an operator combines with a number to give a result.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inverse<N> {
+    /// No value gives the result, not even one too large for an `N`.
+    Impossible,
+    /// Only this value gives the result.
+    Unique(N),
+    /// Several values might give the result.
+    Unknown,
+}
+
+impl<N, O: Operator<N> + ?Sized> Operator<N> for &O {
+    fn apply(&self, a: &N, b: &N) -> Option<N> {
+        (**self).apply(a, b)
+    }
+
+    fn symbol(&self) -> &str {
+        (**self).symbol()
+    }
+
+    fn grows(&self, b: &N) -> bool {
+        (**self).grows(b)
+    }
+
+    fn grows_with_larger(&self) -> bool {
+        (**self).grows_with_larger()
+    }
+
+    fn constant(&self, b: &N) -> Option<N> {
+        (**self).constant(b)
+    }
+
+    fn precedence(&self) -> u8 {
+        (**self).precedence()
+    }
+
+    fn invert(&self, c: &N, b: &N) -> Inverse<N> {
+        (**self).invert(c, b)
+    }
+}
+
+/// The operators which come with the solver. Sets of them are solved without the cost of
+/// dynamic dispatch; other operators can be mixed in as `&dyn Operator`s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Builtin {
+    /// `a + b`.
+    Add,
+    /// `a * b`.
+    Multiply,
+    /// The digits of `a` followed by the digits of `b`.
+    Concatenate,
+    /// `a - b`, which is undefined if `b` is larger, as there are no negative values.
+    Subtract,
+    /// `a / b`, rounded down, which is undefined if `b` is zero.
+    Divide,
+    /// The bitwise exclusive or of `a` and `b`.
+    Xor,
+    /// `a` to the power of `b`, where zero to the power of zero is one.
+    Power,
+}
+
+impl Builtin {
+    pub const ALL: [Builtin; 7] = [
+        Builtin::Add,
+        Builtin::Multiply,
+        Builtin::Concatenate,
+        Builtin::Subtract,
+        Builtin::Divide,
+        Builtin::Xor,
+        Builtin::Power,
+    ];
+}
+
+impl<N: Number> Operator<N> for Builtin {
+    fn apply(&self, a: &N, b: &N) -> Option<N> {
+        match self {
+            Builtin::Add => a.checked_add(b),
+            Builtin::Multiply => a.checked_mul(b),
+            Builtin::Concatenate => a.checked_concatenate(b),
+            Builtin::Subtract => a.checked_sub(b),
+            Builtin::Divide => a.checked_div(b),
+            Builtin::Xor => Some(a.xor(b)),
+            Builtin::Power => a.checked_pow(b),
+        }
+    }
+
+    fn symbol(&self) -> &str {
+        match self {
+            Builtin::Add => "+",
+            Builtin::Multiply => "*",
+            Builtin::Concatenate => "||",
+            Builtin::Subtract => "-",
+            Builtin::Divide => "/",
+            Builtin::Xor => "^",
+            Builtin::Power => "**",
+        }
+    }
+
+    fn grows(&self, b: &N) -> bool {
+        match self {
+            Builtin::Add | Builtin::Concatenate => true,
+            Builtin::Multiply | Builtin::Power => !b.is_zero(),
+            Builtin::Subtract | Builtin::Xor => b.is_zero(),
+            Builtin::Divide => *b == N::from(1),
+        }
+    }
+
+    fn grows_with_larger(&self) -> bool {
+        match self {
+            Builtin::Add | Builtin::Concatenate | Builtin::Multiply | Builtin::Power => true,
+            Builtin::Subtract | Builtin::Xor | Builtin::Divide => false,
+        }
+    }
+
+    fn constant(&self, b: &N) -> Option<N> {
+        match self {
+            Builtin::Multiply if b.is_zero() => Some(b.clone()),
+            Builtin::Power if b.is_zero() => Some(N::from(1)),
+            _ => None,
+        }
+    }
+
+    /// Concatenation binds most tightly, like the digits of a number, then powers, multiplication
+    /// and division, addition and subtraction, and xor least tightly.
+    fn precedence(&self) -> u8 {
+        match self {
+            Builtin::Concatenate => 5,
+            Builtin::Power => 4,
+            Builtin::Multiply | Builtin::Divide => 3,
+            Builtin::Add | Builtin::Subtract => 2,
+            Builtin::Xor => 1,
+        }
+    }
+
+    fn invert(&self, c: &N, b: &N) -> Inverse<N> {
+        let one = N::from(1);
+        let unique = |a: Option<N>| a.map_or(Inverse::Impossible, Inverse::Unique);
+        match self {
+            Builtin::Add => unique(c.checked_sub(b)),
+            // Anything multiplied by zero is zero, and anything to the power of zero is one.
+            Builtin::Multiply | Builtin::Power if b.is_zero() => {
+                if Operator::<N>::constant(self, b).as_ref() == Some(c) {
+                    Inverse::Unknown
+                } else {
+                    Inverse::Impossible
+                }
+            }
+            Builtin::Multiply => unique(
+                c.checked_div(b)
+                    .filter(|a| a.checked_mul(b).as_ref() == Some(c)),
+            ),
+            Builtin::Concatenate => unique(c.strip_suffix(b)),
+            Builtin::Subtract => unique(c.checked_add(b)),
+            Builtin::Divide | Builtin::Power if *b == one => Inverse::Unique(c.clone()),
+            Builtin::Divide if b.is_zero() => Inverse::Impossible,
+            Builtin::Xor => Inverse::Unique(c.xor(b)),
+            Builtin::Divide | Builtin::Power => Inverse::Unknown,
+        }
+    }
+}
+
+/// The operators for part 1: `+` and `*`.
+pub const PART1: [Builtin; 2] = [Builtin::Add, Builtin::Multiply];
+
+/// The operators for part 2: `+`, `*` and concatenation.
+pub const PART2: [Builtin; 3] = [Builtin::Add, Builtin::Multiply, Builtin::Concatenate];
+
+/// Returns the operators for the given part of the puzzle.
+pub fn for_part(part: Part) -> &'static [Builtin] {
+    match part {
+        Part::One => &PART1,
+        Part::Two => &PART2,
+    }
+}
+
+/// Combines `first` with each of the numbers in `rest` in turn, left to right, with the operator
+/// paired with it. Returns `None` if the result is undefined or too large for an `N`.
+///
+/// A value which is too large part way through can still lead to a result: it stays too large
+/// through operators which grow, and is replaced through operators which give a constant. Any
+/// other operator abandons it.
+pub fn evaluate<'a, N: Number, O: Operator<N>>(
+    first: &N,
+    rest: impl IntoIterator<Item = (O, &'a N)>,
+) -> Option<N> {
+    // `None` is a value too large for an `N`.
+    let mut value = Some(first.clone());
+    for (operator, n) in rest {
+        value = step(&operator, value.as_ref(), n)?;
+    }
+    value
+}
+
+/// Combines `value` with `n`, where a `value` of `None` is too large for an `N`. Returns the
+/// combined value, `None` inside if that is too large, or `None` if the value is abandoned.
+pub(crate) fn step<N: Number, O: Operator<N>>(
+    operator: &O,
+    value: Option<&N>,
+    n: &N,
+) -> Option<Option<N>> {
+    let combined = match value {
+        Some(value) => operator.apply(value, n),
+        None => operator.constant(n),
+    };
+    match combined {
+        Some(combined) => Some(Some(combined)),
+        None if operator.grows(n) => Some(None),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Builtin, Inverse, Operator};
+
+    #[test]
+    fn test_apply() {
+        let apply = |operator: Builtin, a: u64, b| operator.apply(&a, &b);
+        assert_eq!(apply(Builtin::Add, 12, 34), Some(46));
+        assert_eq!(apply(Builtin::Multiply, 12, 34), Some(408));
+        assert_eq!(apply(Builtin::Concatenate, 12, 34), Some(1234));
+        assert_eq!(apply(Builtin::Subtract, 34, 12), Some(22));
+        assert_eq!(apply(Builtin::Subtract, 12, 34), None);
+        assert_eq!(apply(Builtin::Divide, 34, 12), Some(2));
+        assert_eq!(apply(Builtin::Divide, 34, 0), None);
+        assert_eq!(apply(Builtin::Xor, 12, 10), Some(6));
+        assert_eq!(apply(Builtin::Power, 2, 10), Some(1024));
+        assert_eq!(apply(Builtin::Power, 0, 0), Some(1));
+        assert_eq!(apply(Builtin::Power, 1, u64::MAX), Some(1));
+        assert_eq!(apply(Builtin::Power, 2, 64), None);
+    }
+
+    #[test]
+    fn test_grows() {
+        // Every operator which claims to grow with `b` gives at least `a`, or is too large.
+        for operator in Builtin::ALL {
+            for a in (0u64..40).chain([u64::MAX - 1, u64::MAX]) {
+                for b in 0..40 {
+                    if operator.grows(&b) {
+                        assert!(operator.apply(&a, &b).is_none_or(|value| value >= a));
+                    }
+                    if let Some(constant) = operator.constant(&b) {
+                        assert_eq!(operator.apply(&a, &b), Some(constant));
+                    }
+                }
+            }
+            // And one which claims to grow with larger numbers does grow with them.
+            if Operator::<u64>::grows_with_larger(&operator) {
+                let grows: Vec<bool> = (0u64..40).map(|b| operator.grows(&b)).collect();
+                assert!(grows.windows(2).all(|pair| pair[1] >= pair[0]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_invert() {
+        // Every inverse which an operator gives is the only value which gives the result.
+        let values: Vec<u64> = (0u64..60).chain([u64::MAX - 1, u64::MAX]).collect();
+        for operator in Builtin::ALL {
+            for &b in &values {
+                for &c in &values {
+                    let gives_c = |a: &u64| operator.apply(a, &b) == Some(c);
+                    match operator.invert(&c, &b) {
+                        Inverse::Impossible => {
+                            assert!(!values.iter().any(gives_c), "{:?} {} {}", operator, b, c);
+                            assert_ne!(operator.constant(&b), Some(c));
+                        }
+                        Inverse::Unique(a) => {
+                            assert!(gives_c(&a), "{:?} {} {}", operator, b, c);
+                            assert!(values
+                                .iter()
+                                .filter(|&&other| other != a)
+                                .all(|a| !gives_c(a)));
+                            assert_ne!(operator.constant(&b), Some(c));
+                        }
+                        Inverse::Unknown => {}
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            Builtin::Concatenate.invert(&1234u64, &34),
+            Inverse::Unique(12)
+        );
+        assert_eq!(Builtin::Multiply.invert(&10u64, &3), Inverse::Impossible);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        use Builtin::{Add, Multiply, Subtract};
+        let evaluate = |first: u64, rest: &[(Builtin, u64)]| {
+            super::evaluate(&first, rest.iter().map(|(operator, n)| (operator, n)))
+        };
+        assert_eq!(evaluate(81, &[(Multiply, 40), (Add, 27)]), Some(3267));
+        assert_eq!(evaluate(5, &[(Subtract, 7), (Multiply, 0)]), None);
+        let too_large = [(Multiply, 2), (Multiply, 0), (Add, 1)];
+        assert_eq!(evaluate(u64::MAX, &too_large), Some(1));
+        let abandoned = [(Multiply, 2), (Subtract, 1), (Multiply, 0)];
+        assert_eq!(evaluate(u64::MAX, &abandoned), None);
+    }
+}
@@ -0,0 +1,401 @@
+//! Which operators in an equation are applied first.
+//!
+//! The puzzle applies operators strictly left to right, but equations can also be evaluated with
+//! standard precedence, multiplication before addition, or with a table of precedences. The
+//! solver, witnesses and generators all take a [`Precedence`].
+//!
+//! Operators of equal precedence are applied left to right. A value too large for an `N` can't be
+//! on the right of an operator, so an equation whose operators on the right of another overflow
+//! is never true.
+
+use crate::{operator, Number, Operator};
+
+/// How tightly each operator binds. Operators which bind more tightly are applied first.
+pub enum Precedence<O> {
+    /// Every operator binds as tightly as any other, so they are applied left to right, as in
+    /// the puzzle.
+    LeftToRight,
+    /// Operators bind as tightly as `Operator::precedence` says, as in normal maths.
+    Standard,
+    /// Operators bind as tightly as the function says.
+    Table(fn(&O) -> u8),
+}
+
+// Derived impls would require `O: Clone`, which function pointers don't need.
+impl<O> Clone for Precedence<O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<O> Copy for Precedence<O> {}
+
+impl<O> std::fmt::Debug for Precedence<O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Precedence::LeftToRight => write!(f, "LeftToRight"),
+            Precedence::Standard => write!(f, "Standard"),
+            Precedence::Table(_) => write!(f, "Table"),
+        }
+    }
+}
+
+impl<O> std::str::FromStr for Precedence<O> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "left-to-right" => Ok(Precedence::LeftToRight),
+            "standard" => Ok(Precedence::Standard),
+            _ => Err(format!(
+                "unknown precedence `{}`, expected `left-to-right` or `standard`",
+                s
+            )),
+        }
+    }
+}
+
+impl<O> Precedence<O> {
+    /// Returns how tightly the operator binds.
+    pub fn of<N>(&self, operator: &O) -> u8
+    where
+        O: Operator<N>,
+    {
+        match self {
+            Precedence::LeftToRight => 0,
+            Precedence::Standard => operator.precedence(),
+            Precedence::Table(table) => table(operator),
+        }
+    }
+}
+
+/// Combines `first` with each of the numbers in `rest`, with the operator paired with it, applying
+/// the operators in order of precedence. Returns `None` if the result is undefined or too large
+/// for an `N`. With `Precedence::LeftToRight` this is `operator::evaluate`.
+pub fn evaluate<'a, N: Number, O: Operator<N> + 'a>(
+    precedence: Precedence<O>,
+    first: &N,
+    rest: impl IntoIterator<Item = (&'a O, &'a N)>,
+) -> Option<N> {
+    let mut partial = Partial::new(first);
+    for (operator, n) in rest {
+        let binds = precedence.of(operator);
+        partial.push(operator, binds, n)?;
+    }
+    partial.finish()?
+}
+
+/// An equation part way through being evaluated: the operators waiting for the operators after
+/// them, which bind more tightly, to be applied first, and the value on the right of the last.
+/// A value of `None` is too large for an `N`.
+pub(crate) struct Partial<N, O> {
+    waiting: Vec<(Option<N>, O, u8)>,
+    value: Option<N>,
+    /// The operators applied by each `push_undoable`, for `undo` to put back.
+    applied: Vec<(Option<N>, O, u8)>,
+}
+
+/// What one `Partial::push_undoable` changed.
+pub(crate) struct Undo<N> {
+    applied: usize,
+    value: Option<N>,
+}
+
+impl<N: Number, O: Operator<N>> Partial<N, O> {
+    pub(crate) fn new(first: &N) -> Self {
+        Partial {
+            waiting: Vec::new(),
+            value: Some(first.clone()),
+            applied: Vec::new(),
+        }
+    }
+
+    /// Adds the operator, which binds as tightly as `binds`, and the number after it. Returns
+    /// `None` if the value is abandoned.
+    pub(crate) fn push(&mut self, operator: O, binds: u8, n: &N) -> Option<()> {
+        let (waiting, value) = self.apply_waiting(binds)?;
+        self.waiting.truncate(waiting);
+        self.waiting.push((value, operator, binds));
+        self.value = Some(n.clone());
+        Some(())
+    }
+
+    /// Adds the operator and number like `push`, returning what changed for `undo`. Nothing
+    /// changes if the value is abandoned.
+    pub(crate) fn push_undoable(&mut self, operator: O, binds: u8, n: &N) -> Option<Undo<N>> {
+        let (waiting, value) = self.apply_waiting(binds)?;
+        let applied = self.waiting.len() - waiting;
+        self.applied.extend(self.waiting.drain(waiting..));
+        self.waiting.push((value, operator, binds));
+        let value = self.value.replace(n.clone());
+        Some(Undo { applied, value })
+    }
+
+    /// Puts back what the last `push_undoable` changed.
+    pub(crate) fn undo(&mut self, undo: Undo<N>) {
+        self.waiting.pop();
+        let applied = self.applied.len() - undo.applied;
+        self.waiting.extend(self.applied.drain(applied..));
+        self.value = undo.value;
+    }
+
+    /// Returns the value on the left of the first waiting operator, or the value so far if none
+    /// are waiting, or `None` if it is too large for an `N`. When every operator grows with
+    /// every value it is applied to, the value of the equation is at least this.
+    pub(crate) fn leftmost(&self) -> Option<&N> {
+        match self.waiting.first() {
+            Some((left, _, _)) => left.as_ref(),
+            None => self.value.as_ref(),
+        }
+    }
+
+    /// Applies the rest of the operators, returning the value of the equation, or `None` if it
+    /// is abandoned.
+    pub(crate) fn finish(&self) -> Option<Option<N>> {
+        let (_, value) = self.apply_waiting(0)?;
+        Some(value)
+    }
+
+    /// Returns how many operators are still waiting once those which bind at least as tightly as
+    /// `binds` are applied, and the value they give, or `None` if it is abandoned.
+    fn apply_waiting(&self, binds: u8) -> Option<(usize, Option<N>)> {
+        let mut value = self.value.clone();
+        let mut waiting = self.waiting.len();
+        while waiting > 0 && self.waiting[waiting - 1].2 >= binds {
+            let (left, operator, _) = &self.waiting[waiting - 1];
+            let right = value?;
+            value = operator::step(operator, left.as_ref(), &right)?;
+            waiting -= 1;
+        }
+        Some((waiting, value))
+    }
+}
+
+/// Returns the operators which combine the nums into the target, applied in order of
+/// precedence, if any do. An operator which grows with every number may still not grow with the
+/// value of the operators after it which bind more tightly, as `10 / (1 + 1)` shows, so values
+/// above the test value are only abandoned when every operator also grows with larger numbers.
+pub(crate) fn search<'a, N: Number, O: Operator<N>>(
+    target: &N,
+    nums: &[N],
+    operators: &'a [O],
+    precedence: Precedence<O>,
+) -> Option<Vec<&'a O>> {
+    let (first, rest) = nums.split_first()?;
+    let solver = Solver {
+        target,
+        operators,
+        precedence,
+        prune: can_prune(operators, rest),
+    };
+    let mut chosen = Vec::with_capacity(rest.len());
+    solver
+        .reaches_target(&mut Partial::new(first), rest, &mut chosen)
+        .then_some(chosen)
+}
+
+/// Returns whether every operator grows with every one of the nums and with every larger value,
+/// so the value of an equation is at least its `Partial::leftmost` value.
+pub(crate) fn can_prune<N, O: Operator<N>>(operators: &[O], nums: &[N]) -> bool {
+    operators
+        .iter()
+        .all(|operator| operator.grows_with_larger() && nums.iter().all(|n| operator.grows(n)))
+}
+
+struct Solver<'t, 'a, N, O> {
+    target: &'t N,
+    operators: &'a [O],
+    precedence: Precedence<O>,
+    /// Whether values above the target can be abandoned, as `can_prune` says.
+    prune: bool,
+}
+
+impl<'a, N: Number, O: Operator<N>> Solver<'_, 'a, N, O> {
+    /// Returns whether the partial equation can be finished with the nums into the target,
+    /// adding the operators which do so to `chosen`. The partial equation is left as it was.
+    fn reaches_target(
+        &self,
+        partial: &mut Partial<N, &'a O>,
+        nums: &[N],
+        chosen: &mut Vec<&'a O>,
+    ) -> bool {
+        if self.prune && partial.leftmost().is_none_or(|value| value > self.target) {
+            return false;
+        }
+        let Some((first, rest)) = nums.split_first() else {
+            return partial.finish().flatten().as_ref() == Some(self.target);
+        };
+        for operator in self.operators {
+            let binds = self.precedence.of(operator);
+            let Some(undo) = partial.push_undoable(operator, binds, first) else {
+                continue;
+            };
+            chosen.push(operator);
+            let reached = self.reaches_target(partial, rest, chosen);
+            partial.undo(undo);
+            if reached {
+                return true;
+            }
+            chosen.pop();
+        }
+        false
+    }
+}
+
+/// Writes the equation with the operators applied in order of precedence, with brackets where
+/// that differs from the order standard precedence gives.
+pub(crate) fn write_equation<N: Number, O: Operator<N>>(
+    f: &mut std::fmt::Formatter,
+    nums: &[N],
+    operators: &[&O],
+    precedence: Precedence<O>,
+    target: &N,
+) -> std::fmt::Result {
+    let (first, rest) = nums.split_first().expect("an equation has a number");
+    // Each term is written with the standard precedence of its last operator, if it has one.
+    let mut waiting: Vec<(String, Option<u8>, &O, u8)> = Vec::new();
+    let mut term = (first.to_string(), None);
+    let apply = |(left, left_binds): (String, Option<u8>), operator: &O, right| {
+        let (right, right_binds): (String, Option<u8>) = right;
+        let binds = operator.precedence();
+        let left = match left_binds {
+            Some(left_binds) if left_binds < binds => format!("({})", left),
+            _ => left,
+        };
+        let right = match right_binds {
+            Some(right_binds) if right_binds <= binds => format!("({})", right),
+            _ => right,
+        };
+        let term = format!("{} {} {}", left, operator.symbol(), right);
+        (term, Some(binds))
+    };
+    for (&operator, n) in operators.iter().zip(rest) {
+        let binds = precedence.of(operator);
+        while waiting
+            .last()
+            .is_some_and(|(_, _, _, other)| *other >= binds)
+        {
+            let (left, left_binds, waiting_operator, _) = waiting.pop().expect("one is waiting");
+            term = apply((left, left_binds), waiting_operator, term);
+        }
+        let (left, left_binds) = std::mem::replace(&mut term, (n.to_string(), None));
+        waiting.push((left, left_binds, operator, binds));
+    }
+    while let Some((left, left_binds, operator, _)) = waiting.pop() {
+        term = apply((left, left_binds), operator, term);
+    }
+    write!(f, "{} = {}", term.0, target)
+}
+
+/// Addition before multiplication, the opposite of standard precedence, as a table for tests.
+#[cfg(test)]
+pub(crate) fn add_first(operator: &crate::operator::Builtin) -> u8 {
+    match operator {
+        crate::operator::Builtin::Add => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_first, Precedence};
+    use crate::operator::{Builtin, PART2};
+    use crate::Operator;
+    use aoc_common::property::Property;
+
+    fn evaluate(
+        precedence: Precedence<Builtin>,
+        first: u64,
+        rest: &[(Builtin, u64)],
+    ) -> Option<u64> {
+        super::evaluate(
+            precedence,
+            &first,
+            rest.iter().map(|(operator, n)| (operator, n)),
+        )
+    }
+
+    #[test]
+    fn test_evaluate() {
+        use Builtin::{Add, Concatenate, Multiply, Power, Subtract};
+        let rest = [(Add, 40), (Multiply, 27)];
+        assert_eq!(evaluate(Precedence::LeftToRight, 81, &rest), Some(3267));
+        assert_eq!(evaluate(Precedence::Standard, 81, &rest), Some(1161));
+        let rest = [(Multiply, 3), (Add, 4)];
+        assert_eq!(evaluate(Precedence::Standard, 2, &rest), Some(10));
+        assert_eq!(evaluate(Precedence::Table(add_first), 2, &rest), Some(14));
+        let rest = [(Subtract, 3), (Subtract, 4)];
+        assert_eq!(evaluate(Precedence::Standard, 10, &rest), Some(3));
+        let rest = [(Add, 2), (Power, 3), (Concatenate, 1)];
+        assert_eq!(
+            evaluate(Precedence::Standard, 1, &rest),
+            Some(1 + (1 << 31))
+        );
+        // The power on the right of the multiplication overflows.
+        let rest = [(Multiply, 2), (Power, 64)];
+        assert_eq!(evaluate(Precedence::LeftToRight, 0, &rest), Some(0));
+        assert_eq!(evaluate(Precedence::Standard, 0, &rest), None);
+    }
+
+    #[test]
+    fn test_left_to_right_is_evaluate() {
+        let same: Property<(u64, Vec<(u8, u64)>)> = Property {
+            name: "evaluating left to right matches operator::evaluate",
+            holds: |(first, rest)| {
+                let rest: Vec<(Builtin, u64)> = rest
+                    .iter()
+                    .map(|&(operator, n)| (Builtin::ALL[operator as usize], n))
+                    .collect();
+                let steps = rest.iter().map(|(operator, n)| (operator, n));
+                evaluate(Precedence::LeftToRight, *first, &rest)
+                    == crate::operator::evaluate(first, steps)
+            },
+        };
+        same.check(2000, |rng| {
+            let rest = (0..rng.below(6))
+                .map(|_| (rng.below(7) as u8, rng.below(12)))
+                .collect();
+            (rng.below(12), rest)
+        });
+    }
+
+    #[test]
+    fn test_search() {
+        let search = |target: usize, nums: &[usize], precedence| {
+            super::search(&target, nums, &PART2, precedence).map(|chosen| {
+                chosen
+                    .iter()
+                    .map(|operator| Operator::<usize>::symbol(*operator))
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(
+            search(1161, &[81, 40, 27], Precedence::Standard),
+            Some(vec!["+", "*"])
+        );
+        assert_eq!(search(1161, &[81, 40, 27], Precedence::LeftToRight), None);
+        assert_eq!(
+            search(14, &[2, 3, 4], Precedence::Table(add_first)),
+            Some(vec!["*", "+"])
+        );
+        // The division doesn't grow with the value of the addition after it.
+        let operators = [Builtin::Add, Builtin::Divide];
+        let chosen = super::search(
+            &5usize,
+            &[10, 1, 1],
+            &operators,
+            Precedence::Table(add_first),
+        );
+        assert_eq!(chosen, Some(vec![&Builtin::Divide, &Builtin::Add]));
+        assert_eq!(search(5, &[5], Precedence::Standard), Some(vec![]));
+        assert_eq!(search(5, &[], Precedence::Standard), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let parse = |s: &str| s.parse::<Precedence<Builtin>>().map(|p| format!("{:?}", p));
+        assert_eq!(parse("standard").as_deref(), Ok("Standard"));
+        assert_eq!(parse("left-to-right").as_deref(), Ok("LeftToRight"));
+        assert!(parse("right-to-left").is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Writes a seed corpus for every fuzz target, from each day's example input and a few small
+//! generated inputs, so fuzzing starts from inputs which get past the parsers' first checks.
+//!
+//!     cargo run --manifest-path fuzz/Cargo.toml --example seed_corpus
+//!
+//! Run it from the repository root, as `cargo fuzz` expects the corpus in `fuzz/corpus`.
+
+use std::path::Path;
+
+/// Seeds for the generated inputs.
+const SEEDS: std::ops::Range<u64> = 0..8;
+
+fn write(target: &str, name: &str, input: &[u8]) {
+    let dir = Path::new("fuzz/corpus").join(target);
+    std::fs::create_dir_all(&dir)
+        .unwrap_or_else(|err| panic!("cannot create {}: {}", dir.display(), err));
+    let path = dir.join(name);
+    std::fs::write(&path, input)
+        .unwrap_or_else(|err| panic!("cannot write {}: {}", path.display(), err));
+}
+
+fn seed(target: &str, example: &str, generate: impl Fn(u64) -> String) {
+    write(target, "example", example.trim_start().as_bytes());
+    for seed in SEEDS {
+        write(
+            target,
+            &format!("generated-{}", seed),
+            generate(seed).as_bytes(),
+        );
+    }
+}
+
+fn main() {
+    seed("day1", day1::EXAMPLE_INPUT, |seed| {
+        day1::generator::generate(seed, 20)
+    });
+    seed("day2", day2::EXAMPLE_INPUT, |seed| {
+        day2::generator::generate(seed, 20)
+    });
+    seed("day3", day3::EXAMPLE_INPUT, |seed| {
+        day3::generator::generate(seed, 400)
+    });
+    // The scanner target reads its first byte as the chunk size.
+    seed(
+        "day3_scanner",
+        &format!("\x05{}", day3::EXAMPLE_INPUT),
+        |seed| {
+            format!(
+                "{}{}",
+                (seed + 1) as u8 as char,
+                day3::generator::generate(seed, 400)
+            )
+        },
+    );
+    seed("day4", day4::EXAMPLE_INPUT, |seed| {
+        day4::generator::generate(seed, 12)
+    });
+    seed("day5", day5::EXAMPLE_INPUT, |seed| {
+        day5::generator::generate(seed, 10)
+    });
+    seed("day6", day6::EXAMPLE_INPUT, |seed| {
+        day6::generator::generate(seed, 12)
+    });
+    seed("day7", day7::EXAMPLE_INPUT, |seed| {
+        day7::generator::generate(seed, 20)
+    });
+}
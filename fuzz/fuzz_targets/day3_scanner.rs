@@ -0,0 +1,27 @@
+//! Feeds arbitrary memory to the day 3 `Scanner` in chunks, and checks the totals don't depend on
+//! where the chunks split it. Memory which is valid UTF-8 is also checked against the regex-based
+//! `sum_muls` and `sum_enabled_muls`.
+
+#![no_main]
+
+use day3::Scanner;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // The first byte picks the chunk size, and the rest is the memory.
+    let Some((&chunk_size, memory)) = data.split_first() else {
+        return;
+    };
+    let mut whole = Scanner::new();
+    whole.feed(memory);
+    let mut chunked = Scanner::new();
+    for chunk in memory.chunks(chunk_size.max(1) as usize) {
+        chunked.feed(chunk);
+    }
+    assert_eq!(whole.totals(), chunked.totals());
+    if let Ok(memory) = std::str::from_utf8(memory) {
+        let totals = whole.totals();
+        assert_eq!(totals.all, day3::sum_muls(memory));
+        assert_eq!(totals.enabled, day3::sum_enabled_muls(memory));
+    }
+});
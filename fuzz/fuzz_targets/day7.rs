@@ -0,0 +1,10 @@
+//! Parses arbitrary bytes as a day 7 input, which may fail but must not panic or hang.
+
+#![no_main]
+
+use aoc_common::Solution;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = day7::Day7::parse(data);
+});